@@ -11,14 +11,37 @@ const SUPPORTED_TYPES: [&'static str; 17] = [
     "bool", "char", "String"
 ];
 
-fn filter_values(f: Field) -> bool {
-    match f.ty {
+fn is_supported(t: &Type) -> bool {
+    match t {
         syn::Type::Path(path) => SUPPORTED_TYPES.contains(&path.path.segments[0].ident.to_string().as_str()),
         _ => false
     }
 }
 
+// The type in `Option<...>`, for fields that may have no value
+fn option_inner(t: &Type) -> Option<&Type> {
+    match t {
+        syn::Type::Path(path) if path.path.segments[0].ident == "Option" => match &path.path.segments[0].arguments {
+            syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => Some(inner),
+                _ => None
+            },
+            _ => None
+        },
+        _ => None
+    }
+}
+
+fn filter_values(f: Field) -> bool {
+    is_supported(&f.ty) || option_inner(&f.ty).map(is_supported).unwrap_or(false)
+}
+
+// Fields without a value are given as an empty string
 fn type_to_string(ident: Ident, t: Type) -> TokenStream2 {
+    if option_inner(&t).is_some() {
+        return quote! { self.#ident.as_ref().map(|v| v.to_string()).unwrap_or_default() }
+    }
+
     match t {
         syn::Type::Path(path) => if SUPPORTED_TYPES.contains(&path.path.segments[0].ident.to_string().as_str()) {
             quote! { self.#ident.to_string() }
@@ -29,6 +52,27 @@ fn type_to_string(ident: Ident, t: Type) -> TokenStream2 {
     }
 }
 
+// An empty string clears fields that may have no value
+fn parse_value(ident: Ident, t: Type) -> TokenStream2 {
+    match option_inner(&t) {
+        Some(_) => quote! {
+            self.#ident = match value_string.trim() {
+                "" => None,
+                v => match v.parse() {
+                    Ok(val) => Some(val),
+                    Err(err) => return Err(Box::new(err))
+                }
+            }
+        },
+        None => quote! {
+            self.#ident = match value_string.parse() {
+                Ok(val) => val,
+                Err(err) => return Err(Box::new(err))
+            }
+        }
+    }
+}
+
 fn impl_field_editable_trait(ast: DeriveInput) -> TokenStream {
     // get struct info
     let ident = ast.ident;
@@ -52,6 +96,7 @@ fn impl_field_editable_trait(ast: DeriveInput) -> TokenStream {
     let field_ident_strs: Vec<String> = field_idents.iter().map(|i| i.to_string()).collect();
 
     let val_string: Vec<TokenStream2> = field_types.iter().enumerate().map(|(i, t)| type_to_string(field_idents[i].clone(), t.clone())).collect();
+    let parse_value: Vec<TokenStream2> = field_types.iter().enumerate().map(|(i, t)| parse_value(field_idents[i].clone(), t.clone())).collect();
 
     // generate impl
     let quote = quote! {
@@ -66,10 +111,7 @@ fn impl_field_editable_trait(ast: DeriveInput) -> TokenStream {
 
                 #(
                     if field_string.eq(#field_ident_strs) {
-                        self.#field_idents = match value_string.parse() {
-                            Ok(val) => val,
-                            Err(err) => return Err(Box::new(err))
-                        }
+                        #parse_value
                     }
                 )*
 
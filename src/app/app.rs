@@ -3,24 +3,32 @@ use ratatui::{
     layout::Direction
 };
 use std::{
+    env,
     thread,
     time,
     time::Duration,
     error::Error,
-    thread::sleep,
+    path::PathBuf,
 };
 use std::fmt::format;
 use termion::event::Key;
 
 use crate::{
+    cli::args::SortBy,
     planet_system::{
+        consistency,
+        file_format::FileFormat,
+        load_error::LoadOptions,
         planet_system::PlanetSystem,
         center_star::CenterStar,
         planet::Planet,
+        units::UnitSystem,
     },
     util::{
+        file_reader::read_directory,
         state::{
             list::StatefulList,
+            orbit_view::OrbitViewState,
             tabs::TabsState,
             states::{PopupMode, InputMode}
         }
@@ -45,12 +53,24 @@ pub struct App<'a> {
 
     pub edit_list: StatefulList<PlanetSystem>,
 
-    pub find_list: StatefulList<PlanetSystem>
+    pub find_list: StatefulList<PlanetSystem>,
+
+    pub load_directory: PathBuf,
+    pub load_list: StatefulList<PathBuf>,
+    /// Options for the files opened in the Load tab, bad rows are always skipped
+    pub load_options: LoadOptions,
+    /// Order of the planet systems of the files opened in the Load tab
+    pub sort_by: SortBy,
+
+    pub unit_system: UnitSystem,
+
+    pub orbit_view: OrbitViewState,
 }
 
 impl<'a> App<'a> {
-    pub fn new(title: &'a str, enhanced_graphics: bool, planet_systems: Vec<PlanetSystem>, planet_system_names: &'a Vec<String>) -> App<'a> {
-        App {
+    #[allow(clippy::ptr_arg)]
+    pub fn new(title: &'a str, enhanced_graphics: bool, tick_rate: Duration, planet_systems: Vec<PlanetSystem>, planet_system_names: &'a Vec<String>) -> App<'a> {
+        let mut app = App {
             title,
             should_quit: false,
             tabs: TabsState::default(),
@@ -72,14 +92,28 @@ impl<'a> App<'a> {
                 .collect()
             ),
 
-            find_list: StatefulList::new_with_items(planet_system_names.to_vec())
-        }
+            find_list: StatefulList::new_with_items(planet_system_names.to_vec()),
+
+            load_directory: env::current_dir().unwrap_or_default(),
+            load_list: StatefulList::new_with_items(vec![]),
+            load_options: LoadOptions::default(),
+            sort_by: SortBy::default(),
+
+            unit_system: UnitSystem::default(),
+
+            orbit_view: OrbitViewState::new(tick_rate),
+        };
+
+        app.refresh_load_list();
+
+        app
     }
 
     pub fn on_up(&mut self) -> Result<(), Box<dyn Error>> {
         match self.popup_state {
-            PopupMode::Hide => {
-                self.planet_systems_list.previous();
+            PopupMode::Hide => match self.tabs.index {
+                2 => self.load_list.previous(),
+                _ => self.planet_systems_list.previous(),
             }
             PopupMode::PlanetSystem | PopupMode::CenterStar | PopupMode::Planet => {
                 self.edit_list.previous();
@@ -94,8 +128,9 @@ impl<'a> App<'a> {
 
     pub fn on_down(&mut self) -> Result<(), Box<dyn Error>> {
         match self.popup_state {
-            PopupMode::Hide => {
-                self.planet_systems_list.next();
+            PopupMode::Hide => match self.tabs.index {
+                2 => self.load_list.next(),
+                _ => self.planet_systems_list.next(),
             }
             PopupMode::PlanetSystem | PopupMode::CenterStar | PopupMode::Planet => {
                 self.edit_list.next();
@@ -126,6 +161,12 @@ impl<'a> App<'a> {
             (InputMode::Normal, PopupMode::Hide) => {
                 match c {
                     'q' => self.should_quit = true,
+                    'p' | '+' | '-' | 'z' | 'Z' | 'l' | 'r' if self.tabs.index == 3 => self.orbit_view.on_key(c),
+                    'u' => self.unit_system = self.unit_system.next(),
+                    'o' => {
+                        let report = consistency::fill_missing_orbits(&mut self.planet_systems);
+                        self.messages.push(report.to_string());
+                    },
                     'f' => {
                         self.find_list.state.select(Some(0));
                         self.input = String::new();
@@ -133,11 +174,15 @@ impl<'a> App<'a> {
                         self.popup_state = PopupMode::Find;
                         self.input_mode = InputMode::Editing;
                     },
-                    '\n' => {
-                        let index = self.planet_systems_list.state.selected().unwrap_or_default();
-
-                        self.popup_state = PopupMode::PlanetSystem;
-                        self.edit_list.edit_element = Some(self.planet_systems[index].clone());
+                    '\n' => match self.tabs.index {
+                        1 | 3 => self.input_mode = InputMode::Editing,
+                        2 => self.open_load_entry(),
+                        _ => {
+                            let index = self.planet_systems_list.state.selected().unwrap_or_default();
+
+                            self.popup_state = PopupMode::PlanetSystem;
+                            self.edit_list.edit_element = Some(self.planet_systems[index].clone());
+                        }
                     }
                     _ => {}
                 }
@@ -145,6 +190,7 @@ impl<'a> App<'a> {
             (InputMode::Normal, PopupMode::PlanetSystem) => {
                 match c {
                     'q' => self.should_quit = true,
+                    'u' => self.unit_system = self.unit_system.next(),
                     'c' => {
                         self.edit_list.state.select(Some(0));
                         self.popup_state = PopupMode::Hide;
@@ -153,14 +199,19 @@ impl<'a> App<'a> {
                         let planet_system_index = self.planet_systems_list.state.selected().unwrap_or_default();
                         let edit_index = self.edit_list.state.selected().unwrap_or_default();
 
+                        // The fields of the planet system are followed by the stars and the planets
+                        let field_count = self.planet_systems[planet_system_index].get_fields().len();
+                        let star_count = self.planet_systems[planet_system_index].stars.len();
+
                         match edit_index {
-                            0 => self.input_mode = InputMode::Editing,
-                            1 => {
+                            i if i < field_count => self.input_mode = InputMode::Editing,
+                            i if i < field_count + star_count => {
                                 self.popup_state = PopupMode::CenterStar;
+                                self.edit_list.size = i - field_count;
                             }
                             _ => {
                                 self.popup_state = PopupMode::Planet;
-                                self.edit_list.size = edit_index-2;
+                                self.edit_list.size = edit_index - field_count - star_count;
                             }
                         }
                     }
@@ -170,12 +221,18 @@ impl<'a> App<'a> {
             (InputMode::Normal, PopupMode::Planet) => {
                 match c {
                     'q' => self.should_quit = true,
+                    'u' => self.unit_system = self.unit_system.next(),
                     'c' => {
                         self.planet_systems_list.state.select(Some(0));
                         self.popup_state = PopupMode::Hide;
                     },
                     '\n' => {
-                        self.input_mode = InputMode::Editing;
+                        let planet = &self.edit_list.edit_element.as_ref().unwrap().planets[self.edit_list.size];
+
+                        // Derived properties and moons are listed after the fields, and can not be edited
+                        if self.edit_list.state.selected().unwrap_or_default() < planet.get_fields().len() {
+                            self.input_mode = InputMode::Editing;
+                        }
                     }
                     _ => {}
                 }
@@ -183,12 +240,18 @@ impl<'a> App<'a> {
             (InputMode::Normal, PopupMode::CenterStar) => {
                 match c {
                     'q' => self.should_quit = true,
+                    'u' => self.unit_system = self.unit_system.next(),
                     'c' => {
                         self.planet_systems_list.state.select(Some(0));
                         self.popup_state = PopupMode::Hide;
                     },
                     '\n' => {
-                        self.input_mode = InputMode::Editing;
+                        let center_star = &self.edit_list.edit_element.as_ref().unwrap().stars[self.edit_list.size];
+
+                        // Derived properties are listed after the fields, and can not be edited
+                        if self.edit_list.state.selected().unwrap_or_default() < center_star.get_fields().len() {
+                            self.input_mode = InputMode::Editing;
+                        }
                     }
                     _ => {}
                 }
@@ -215,20 +278,11 @@ impl<'a> App<'a> {
                         let planet_system = &mut self.planet_systems[system_index];
                         let planet_system_edit = self.edit_list.edit_element.as_mut().unwrap();
 
-                        match edit_index {
-                            0 => {
-                                planet_system.name = message.clone();
-                                planet_system_edit.name = message.clone();
-                            },
-                            1 => {
-                                planet_system.center_star.name = message.clone();
-                                planet_system_edit.center_star.name = message.clone();
-                            },
-                            _ => {
-                                planet_system.planets[edit_index-2].name = message.clone();
-                                planet_system_edit.planets[edit_index-2].name = message.clone();
-                            }
-                        }
+                        let planet_system_field_name = planet_system.get_fields()[edit_index].0;
+
+                        // All fields of a planet system are strings, so editing them can not fail
+                        planet_system.edit_field(planet_system_field_name, message.clone())?;
+                        planet_system_edit.edit_field(planet_system_field_name, message)?;
 
                         self.input_mode = InputMode::Normal;
                     },
@@ -246,17 +300,26 @@ impl<'a> App<'a> {
 
                         let planet_field_name = planet_system.planets[planet_system_edit_index].get_fields()[self.edit_list.state.selected().unwrap_or_default()].0;
 
-                        match planet_system.planets[planet_system_edit_index].edit_field(planet_field_name, message.to_string()) {
+                        // The input is in the units that are shown
+                        let units = self.unit_system.body_units();
+
+                        match units.field_input(planet_field_name, &message)
+                            .and_then(|value| planet_system.planets[planet_system_edit_index].edit_field(planet_field_name, value)) {
                             Ok(_) => {
+                                // A value that is typed in is measured, not inferred
+                                planet_system.planets[planet_system_edit_index].inferred.clear(planet_field_name);
+
+                                let planet_edit = &mut self.edit_list.edit_element.as_mut().unwrap().planets[planet_system_edit_index];
+
                                 // Ignore error, becuase this is chekced before.
-                                self.edit_list.edit_element.as_mut().unwrap().planets[planet_system_edit_index].edit_field(planet_field_name, message.to_string())?
+                                planet_edit.edit_field(planet_field_name, units.field_input(planet_field_name, &message)?)?;
+                                planet_edit.inferred.clear(planet_field_name);
                             }
                             Err(e) => {
-                                println!("{:#?}", e.to_string());
-                                sleep(Duration::from_secs(5));
+                                // The terminal is in raw mode, so errors are shown with the other messages
+                                self.messages.push(format!("Could not set {} to '{}': {}", planet_field_name, message, e));
                                 self.input_mode = InputMode::Normal;
 
-                                // Return before next function is run to not print two error messages to the screen.
                                 return Ok(())
                             }
                         };
@@ -271,21 +334,33 @@ impl<'a> App<'a> {
                     '\n' => {
                         let message: String = self.input.drain(..).collect();
 
+                        let star_index = self.edit_list.size;
+
                         let planet_system = &mut self.planet_systems[self.planet_systems_list.state.selected().unwrap_or_default()];
 
-                        let center_star_field_name = planet_system.center_star.get_fields()[self.edit_list.state.selected().unwrap_or_default()].0;
+                        let center_star_field_name = planet_system.stars[star_index].get_fields()[self.edit_list.state.selected().unwrap_or_default()].0;
+                        let old_name = planet_system.stars[star_index].name.clone();
+
+                        // The input is in the units that are shown
+                        let units = self.unit_system.body_units();
 
-                        match planet_system.center_star.edit_field(center_star_field_name, message.to_string()) {
+                        match units.field_input(center_star_field_name, &message)
+                            .and_then(|value| planet_system.stars[star_index].edit_field(center_star_field_name, value)) {
                             Ok(_) => {
+                                // Planets orbiting a renamed star keep orbiting it
+                                planet_system.rename_host(&old_name, &planet_system.stars[star_index].name.clone());
+
+                                let planet_system_edit = self.edit_list.edit_element.as_mut().unwrap();
+
                                 // Ignore error, becuase this is chekced before.
-                                self.edit_list.edit_element.as_mut().unwrap().center_star.edit_field(center_star_field_name, message.to_string())?;
+                                planet_system_edit.stars[star_index].edit_field(center_star_field_name, units.field_input(center_star_field_name, &message)?)?;
+                                planet_system_edit.rename_host(&old_name, &planet_system_edit.stars[star_index].name.clone());
                             }
                             Err(e) => {
-                                println!("{:#?}", e.to_string());
-                                sleep(Duration::from_secs(5));
+                                // The terminal is in raw mode, so errors are shown with the other messages
+                                self.messages.push(format!("Could not set {} to '{}': {}", center_star_field_name, message, e));
                                 self.input_mode = InputMode::Normal;
 
-                                // Return before next function is run to not print two error messages to the screen.
                                 return Ok(())
                             }
                         };
@@ -295,6 +370,23 @@ impl<'a> App<'a> {
                     c => self.input.push(c)
                 }
             }
+            (InputMode::Editing, PopupMode::Hide) => {
+                match c {
+                    '\n' if self.tabs.index == 3 => {
+                        let date: String = self.input.drain(..).collect();
+
+                        self.orbit_view.go_to(&date);
+                        self.input_mode = InputMode::Normal;
+                    }
+                    '\n' => {
+                        let file_name: String = self.input.drain(..).collect();
+
+                        self.save_planet_systems(file_name);
+                        self.input_mode = InputMode::Normal;
+                    }
+                    c => self.input.push(c)
+                }
+            }
             (InputMode::Editing, PopupMode::Find) => {
                 match c {
                     '\n' => {
@@ -316,7 +408,6 @@ impl<'a> App<'a> {
                     }
                 }
             }
-            _ => {}
         }
 
         Ok(())
@@ -351,8 +442,82 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// Writes all planet systems to `file_name`, as JSON if it ends with `.json` and as CSV otherwise.
+    pub fn save_planet_systems(&mut self, file_name: String) {
+        if file_name.is_empty() {
+            self.messages.push("No file name given".to_string());
+            return;
+        }
+
+        let result = PlanetSystem::save_systems(&self.planet_systems, file_name.clone(), FileFormat::from_extension(&file_name));
+
+        self.messages.push(match result {
+            Ok(_) => format!("Saved {} planet systems to '{}'", self.planet_systems.len(), file_name),
+            Err(e) => format!("Could not save to '{}': {}", file_name, e),
+        });
+    }
+
+    /// Re-reads the entries shown in the load tab from `load_directory`.
+    pub fn refresh_load_list(&mut self) {
+        let mut entries = vec!["../".to_string()];
+
+        match read_directory(&self.load_directory, &[".csv", ".json"]) {
+            Ok(list) => entries.extend(list),
+            Err(e) => self.messages.push(format!("Could not read '{}': {}", self.load_directory.display(), e)),
+        }
+
+        self.load_list = StatefulList::new_with_items(entries);
+    }
+
+    /// Enters the selected directory, or loads the selected file and replaces the current planet systems with it.
+    pub fn open_load_entry(&mut self) {
+        let entry = self.load_list.items[self.load_list.state.selected().unwrap_or_default()].clone();
+
+        if entry == "../" {
+            if let Some(parent) = self.load_directory.parent() {
+                self.load_directory = parent.to_path_buf();
+            }
+
+            self.refresh_load_list();
+        } else if entry.ends_with('/') {
+            self.load_directory.push(entry.trim_end_matches('/'));
+            self.refresh_load_list();
+        } else {
+            self.load_planet_systems(self.load_directory.join(entry).display().to_string());
+        }
+    }
+
+    /// Bad rows in the file are skipped, and listed in the messages.
+    pub fn load_planet_systems(&mut self, file_name: String) {
+        let report = match PlanetSystem::load_systems_from_file(&file_name, &LoadOptions { skip_bad_rows: true, ..self.load_options.clone() }) {
+            Ok(report) => report,
+            Err(e) => {
+                self.messages.push(format!("Could not load {}", e));
+                return;
+            }
+        };
+
+        self.messages.extend(report.warnings.iter().map(|w| format!("Skipped {}", w)));
+
+        if report.planet_systems.is_empty() {
+            self.messages.push(format!("No planet systems in '{}'", file_name));
+            return;
+        }
+
+        let mut planet_systems = report.planet_systems;
+        self.sort_by.sort(&mut planet_systems);
+
+        let planet_system_names: Vec<String> = planet_systems.iter().map(|p| p.name.clone()).collect();
+
+        self.planet_systems_list = StatefulList::new_with_items(planet_system_names.clone());
+        self.find_list = StatefulList::new_with_items(planet_system_names);
+        self.planet_systems = planet_systems;
+
+        self.messages.push(format!("Loaded {} planet systems from '{}'", self.planet_systems.len(), file_name));
+    }
+
     pub fn on_tick(&mut self) -> Result<(), Box<dyn Error>> {
-        // Update progress
+        self.orbit_view.on_tick();
 
         Ok(())
     }
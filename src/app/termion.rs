@@ -14,9 +14,13 @@ use termion::{
     raw::IntoRawMode,
     screen::IntoAlternateScreen,
 };
-use crate::planet_system::planet_system::PlanetSystem;
+use crate::cli::args::SortBy;
+use crate::planet_system::{
+    load_error::LoadOptions,
+    planet_system::PlanetSystem,
+};
 
-pub fn run(tick_rate: Duration, enhanced_graphics: bool, planet_system: Vec<PlanetSystem>) -> Result<(), Box<dyn Error>> {
+pub fn run(tick_rate: Duration, enhanced_graphics: bool, planet_system: Vec<PlanetSystem>, messages: Vec<String>, load_options: LoadOptions, sort_by: SortBy) -> Result<(), Box<dyn Error>> {
     // setup terminal
     let stdout = io::stdout()
         .into_raw_mode()
@@ -30,7 +34,10 @@ pub fn run(tick_rate: Duration, enhanced_graphics: bool, planet_system: Vec<Plan
     let planet_system_names: Vec<String> = planet_system.iter().map(|p| p.name.clone()).collect();
 
     // create app and run it
-    let app = App::new("Planet system", enhanced_graphics, planet_system, &planet_system_names);
+    let mut app = App::new("Planet system", enhanced_graphics, tick_rate, planet_system, &planet_system_names);
+    app.messages = messages;
+    app.load_options = load_options;
+    app.sort_by = sort_by;
 
     run_app(&mut terminal, app, tick_rate)?;
 
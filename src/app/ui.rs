@@ -19,6 +19,7 @@ use crate::{
             tab1::draw_first_tab,
             save::draw_save_tab,
             load::draw_load_tab,
+            orbits::draw_orbits_tab,
             find::draw_find_popup
         },
         app::App
@@ -38,26 +39,43 @@ pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         )
         .split(f.size());
 
-    draw_status_line(f, app, chunks[0], vec![
+    let mut status = vec![
         "'Q' = quit",
         "enter = select/edit",
         "'esc' = cancel",
         "'c' = close popup",
-        "'f' = find"
-    ]);
+        "'f' = find",
+        "'u' = units",
+        "'o' = fill orbits"
+    ];
+
+    // Keys of the orbit view come first, so they are not cut off on narrow terminals
+    if app.tabs.index == 3 {
+        status.splice(1..1, [
+            "'p' = pause",
+            "'+'/'-' = speed",
+            "'z'/'Z' = zoom",
+            "'l' = log scale",
+            "'r' = now"
+        ]);
+    }
+
+    draw_status_line(f, app, chunks[0], status);
 
     draw_tabs(f, app, chunks[1]);
 
     app.tabs.titles = vec![
         "Planet Systems",
         "Save",
-        "Load"
+        "Load",
+        "Orbits"
     ];
 
     match app.tabs.index {
         0 => draw_first_tab(f, app, chunks[2]),
         1 => draw_save_tab(f, app, chunks[2]),
         2 => draw_load_tab(f, app, chunks[2]),
+        3 => draw_orbits_tab(f, app, chunks[2]),
         _ => {}
     };
 
@@ -7,7 +7,10 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 
-use crate::app::app::App;
+use crate::{
+    app::app::App,
+    util::ui::draw_messages,
+};
 
 pub fn draw_load_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
     where
@@ -19,4 +22,41 @@ pub fn draw_load_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
         .style(Style::default());
 
     f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints(
+            [
+                Constraint::Min(0),
+                Constraint::Length(6),
+            ]
+                .as_ref(),
+        )
+        .split(area);
+
+    draw_file_list(f, app, chunks[0]);
+    draw_messages(f, app, chunks[1]);
+}
+
+fn draw_file_list<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+    where
+        B: Backend,
+{
+    let list_elements: Vec<ListItem> = app.load_list.items
+        .iter()
+        .map(|e| ListItem::new(vec![Line::from(Span::raw(e))]))
+        .collect();
+
+    let list = List::new(list_elements)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(app.load_directory.display().to_string())
+        )
+        .highlight_style(Style::default()
+            .add_modifier(Modifier::BOLD)
+        )
+        .highlight_symbol("> ");
+
+    f.render_stateful_widget(list, area, &mut app.load_list.state);
 }
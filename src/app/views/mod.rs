@@ -2,4 +2,5 @@ pub mod tab1;
 pub mod popup;
 pub mod find;
 pub mod save;
-pub mod load;
\ No newline at end of file
+pub mod load;
+pub mod orbits;
@@ -0,0 +1,182 @@
+use ratatui::{
+    backend::Backend,
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{
+        Block,
+        Borders,
+        Paragraph,
+        canvas::{Canvas, Line, Points},
+    },
+};
+
+use crate::{
+    app::app::App,
+    util::state::states::InputMode,
+    planet_system::{
+        celestial_body::OrbitingBody,
+        habitable_zone::Zone,
+        orbit::Orbit,
+        planet::Planet,
+    },
+    util::number_format::format_number,
+};
+
+/// Number of points each orbit is drawn with.
+const ORBIT_POINTS: usize = 128;
+
+/// Planet drawn in the view, with its orbit.
+struct Body {
+    name: String,
+    orbit: Vec<(f64, f64)>,
+    position: (f64, f64),
+    color: Color,
+}
+
+pub fn draw_orbits_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+    where
+        B: Backend,
+{
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+        .split(area);
+
+    draw_orbits(f, app, chunks[0]);
+    draw_date_input(f, app, chunks[1]);
+}
+
+fn draw_orbits<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+    where
+        B: Backend,
+{
+    let index = app.planet_systems_list.state.selected().unwrap_or_default();
+    let planet_system = &app.planet_systems[index];
+    let view = &app.orbit_view;
+
+    // Planets without a semi-major axis or orbital period, or a star, can not be drawn. The files have no orbits of
+    // the stars of a system around each other, so every planet is drawn around the centre, with the stars
+    let planets: Vec<(&Planet, Orbit, Option<Zone>)> = planet_system.planets.iter()
+        .filter_map(|p| {
+            let host = planet_system.host(p)?;
+            Some((p, p.orbit(host.as_ref())?, p.habitable_zone(&host)))
+        })
+        .collect();
+
+    // On the log scale distances are counted in semi-major axes of the innermost planet, so compact systems spread out
+    let innermost = planets.iter()
+        .map(|(_, orbit, _)| orbit.semi_major_axis.km())
+        .fold(f64::INFINITY, f64::min);
+    let scale = |(x, y): (f64, f64)| match view.log_scale {
+        true => {
+            let r = x.hypot(y);
+            let scaled = (1.0 + r / innermost).ln();
+
+            match r > 0.0 {
+                true => (x / r * scaled, y / r * scaled),
+                false => (0.0, 0.0),
+            }
+        }
+        false => (x, y),
+    };
+
+    let bodies: Vec<Body> = planets.iter()
+        .map(|(p, orbit, zone)| Body {
+            name: p.name.clone(),
+            orbit: orbit.path(ORBIT_POINTS).into_iter().map(scale).collect(),
+            position: scale(orbit.state(view.epoch).position),
+            color: match zone {
+                Some(_) => Color::Green,
+                None => Color::Cyan,
+            },
+        })
+        .collect();
+
+    // The view fits the farthest apoapsis, and cells are about twice as high as they are wide
+    let extent = bodies.iter()
+        .flat_map(|b| b.orbit.iter())
+        .map(|(x, y)| x.hypot(*y))
+        .fold(0.0, f64::max)
+        .max(f64::MIN_POSITIVE) * 1.1 / view.zoom;
+    let aspect = area.width.saturating_sub(2) as f64 / (area.height.saturating_sub(2).max(1) as f64 * 2.0);
+
+    let mut title = format!(
+        "Orbits: {} ({}, {} days/s",
+        planet_system.name,
+        view.epoch,
+        format_number(view.speed),
+    );
+
+    if view.log_scale {
+        title.push_str(", log scale");
+    }
+
+    if view.paused {
+        title.push_str(", paused");
+    }
+
+    title.push(')');
+
+    if bodies.len() < planet_system.planets.len() {
+        title.push_str(&format!(" - {} planets without an orbit", planet_system.planets.len() - bodies.len()));
+    }
+
+    let star_name = planet_system.stars.iter().map(|s| s.name.as_str()).collect::<Vec<&str>>().join(" + ");
+
+    let canvas = Canvas::default()
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(title, Style::default()))
+        )
+        .marker(Marker::Braille)
+        .x_bounds([-extent * aspect, extent * aspect])
+        .y_bounds([-extent, extent])
+        .paint(move |ctx| {
+            for body in &bodies {
+                for pair in body.orbit.windows(2) {
+                    ctx.draw(&Line {
+                        x1: pair[0].0,
+                        y1: pair[0].1,
+                        x2: pair[1].0,
+                        y2: pair[1].1,
+                        color: Color::DarkGray,
+                    });
+                }
+            }
+
+            ctx.layer();
+
+            ctx.draw(&Points { coords: &[(0.0, 0.0)], color: Color::Yellow });
+            ctx.print(0.0, 0.0, Span::styled(format!(" {}", star_name), Style::default().fg(Color::Yellow)));
+
+            for body in &bodies {
+                ctx.draw(&Points { coords: &[body.position], color: body.color });
+                ctx.print(body.position.0, body.position.1, Span::styled(format!(" {}", body.name), Style::default().fg(body.color)));
+            }
+        });
+
+    f.render_widget(canvas, area);
+}
+
+fn draw_date_input<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+    where
+        B: Backend,
+{
+    let (title, style) = match &app.orbit_view.error {
+        Some(error) => (error.clone(), Style::default().fg(Color::Red)),
+        None => ("Press enter to go to a date: YYYY-MM-DD, YYYY-MM-DDTHH:MM, JD<julian day> or now".to_string(), Style::default()),
+    };
+
+    f.render_widget(
+        Paragraph::new(app.input.as_str())
+            .style(match app.input_mode {
+                InputMode::Normal => Style::default(),
+                InputMode::Editing => Style::default().fg(Color::Yellow),
+            })
+            .block(Block::default().borders(Borders::ALL).title(Span::styled(title, style))),
+        area
+    );
+}
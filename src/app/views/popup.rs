@@ -1,4 +1,5 @@
 use std::{
+    ops::Range,
     rc::Rc,
     thread::sleep,
     time::Duration
@@ -19,7 +20,7 @@ use crate::{
     app::app::App,
     util::{
         state::states::{PopupMode, InputMode},
-        ui::{FieldEditable, centered_rect, draw_input},
+        ui::{FieldEditable, centered_rect, derived_rows, draw_input},
     },
 };
 
@@ -33,7 +34,7 @@ pub fn draw_popup<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
         PopupMode::PlanetSystem => app.edit_list.edit_element.as_ref().unwrap().name.clone(),
         PopupMode::CenterStar => format!("{} -> {}",
                                          app.edit_list.edit_element.as_ref().unwrap().name.clone(),
-                                         app.edit_list.edit_element.as_ref().unwrap().center_star.name.clone()
+                                         app.edit_list.edit_element.as_ref().unwrap().stars[app.edit_list.size].name.clone()
         ),
         PopupMode::Planet => format!("{} -> {}",
                                      app.edit_list.edit_element.as_ref().unwrap().name.clone(),
@@ -42,12 +43,14 @@ pub fn draw_popup<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
         _ => String::new(),
     };
 
+    let units = app.unit_system.body_units();
+
     let popup_area = centered_rect(60, 60, f.size());
 
     f.render_widget(Clear, popup_area); //this clears out the background
     f.render_widget(
         Block::default()
-        .title(format!("Edit: {}", edit_path))
+        .title(format!("Edit: {} ({} units)", edit_path, app.unit_system.name()))
         .borders(Borders::ALL),
         popup_area
     );
@@ -58,6 +61,7 @@ pub fn draw_popup<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
         .constraints(
             [
                 Constraint::Min(1),
+                Constraint::Length(1),
                 Constraint::Length(3),
             ]
                 .as_ref(),
@@ -74,7 +78,11 @@ pub fn draw_popup<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
                 .map(|f| format!("{}: {}", f.0, f.1))
                 .collect();
 
-            ell_string.push(format!("Center star: {}", edit_element.center_star.get_fields()[0].1));
+            edit_element.stars
+                .iter()
+                .for_each(|s| ell_string.push(
+                    format!("Star: {}", s.name)
+                ));
 
             app.edit_list.edit_element
                 .as_ref()
@@ -97,53 +105,83 @@ pub fn draw_popup<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
             ell
         },
         PopupMode::CenterStar => {
-            let ell_string: Vec<String> = app.edit_list.edit_element
+            let center_star = &app.edit_list.edit_element
                 .as_ref()
                 .unwrap()
-                .center_star
+                .stars[app.edit_list.size];
+
+            let field_count = center_star.get_fields().len();
+
+            let ell_string: Vec<String> = center_star
                 .get_fields()
                 .iter()
-                .map(|f| format!("{}: {}", f.0, f.1))
+                .map(|f| format!("{}: {}", f.0, units.display_field(f.0, &f.1)))
+                .chain(derived_rows(center_star, vec![]))
                 .collect();
 
             app.edit_list.items = ell_string.clone();
 
-            let ell: Vec<ListItem<'static>> = ell_string
-                .iter()
-                .map(|s| ListItem::new(
-                    Line::from(s.to_string())
-                ))
-                .collect();
-
-            ell
+            list_items(&ell_string, field_count..ell_string.len())
         },
         PopupMode::Planet => {
-            let ell_string: Vec<String> = app.edit_list.edit_element
+            let planet_system = app.edit_list.edit_element
                 .as_ref()
-                .unwrap()
-                .planets[app.edit_list.size]
+                .unwrap();
+            let planet = &planet_system.planets[app.edit_list.size];
+
+            let field_count = planet.get_fields().len();
+
+            let mut ell_string: Vec<String> = planet
                 .get_fields()
                 .iter()
-                .map(|f| format!("{}: {}", f.0, f.1))
+                .map(|f| match planet.inferred.field(f.0) {
+                    true => format!("{}: {} (inferred)", f.0, units.display_field(f.0, &f.1)),
+                    false => format!("{}: {}", f.0, units.display_field(f.0, &f.1)),
+                })
+                .chain(derived_rows(planet, planet_system.host(planet).map(|star| planet.orbit_fields(&star)).unwrap_or_default()))
                 .collect();
 
-            app.edit_list.items = ell_string.clone();
+            let derived_end = ell_string.len();
 
-            let ell: Vec<ListItem<'static>> = ell_string
+            // Moons of moons are listed after the moon they orbit
+            planet.all_moons()
                 .iter()
-                .map(|s| ListItem::new(
-                    Line::from(s.to_string())
-                ))
-                .collect();
+                .for_each(|(central, m)| ell_string.push(match central.name() == planet.name {
+                    true => format!("Moon: {}", m.name),
+                    false => format!("Moon: {} (orbits {})", m.name, central.name()),
+                }));
 
-            ell
+            app.edit_list.items = ell_string.clone();
+
+            list_items(&ell_string, field_count..derived_end)
         },
         _ => vec![]
     };
 
     f.render_stateful_widget(get_tasks(edit_elements), chunks[0], &mut app.edit_list.state);
 
-    draw_input(f, app, chunks[1]);
+    // The latest message, so edits that are not accepted do not go unnoticed
+    f.render_widget(
+        Paragraph::new(app.messages.last().cloned().unwrap_or_default())
+            .style(Style::default().fg(Color::Yellow)),
+        chunks[1]
+    );
+
+    draw_input(f, app, chunks[2]);
+}
+
+/// Items for `rows`, with the rows in `read_only` greyed out as they can not be edited.
+fn list_items(rows: &[String], read_only: Range<usize>) -> Vec<ListItem<'static>> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, s)| ListItem::new(Line::from(s.to_string()))
+            .style(if read_only.contains(&i) {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            })
+        )
+        .collect()
 }
 
 fn get_tasks(tasks: Vec<ListItem>) -> List {
@@ -7,7 +7,10 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 
-use crate::app::app::App;
+use crate::{
+    app::app::App,
+    util::ui::{draw_input, draw_messages},
+};
 
 pub fn draw_save_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
     where
@@ -29,12 +32,25 @@ fn draw_save<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
 {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
+        .margin(1)
         .constraints(
             [
+                Constraint::Length(2),
                 Constraint::Length(3),
                 Constraint::Min(0),
                 ]
                 .as_ref(),
         )
         .split(area);
-}
\ No newline at end of file
+
+    f.render_widget(
+        Paragraph::new("Press enter to type a file name and enter again to save. Files ending in '.json' are saved as JSON, everything else as CSV.")
+            .wrap(Wrap {
+                trim: true
+            }),
+        chunks[0]
+    );
+
+    draw_input(f, app, chunks[1]);
+    draw_messages(f, app, chunks[2]);
+}
@@ -2,12 +2,25 @@ use ratatui::{
     backend::Backend,
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 
-use crate::app::app::App;
+use std::ops::Range;
+
+use crate::{
+    app::app::App,
+    planet_system::{
+        celestial_body::{CelestialBody, OrbitingBody},
+        center_star::CenterStar,
+        habitable_zone::HabitableZone,
+        hierarchy::{Member, Tree},
+        planet_system::PlanetSystem,
+        units::{BodyUnits, Length},
+    },
+    util::ui::{derived_rows, draw_messages},
+};
 
 pub fn draw_first_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
     where
@@ -15,6 +28,18 @@ pub fn draw_first_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
 {
     let index = app.planet_systems_list.state.selected().unwrap_or_default();
 
+    // Messages are shown below, as edits and filling in orbits report to them
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(0),
+                Constraint::Length(5),
+            ]
+                .as_ref(),
+        )
+        .split(area);
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
@@ -24,10 +49,11 @@ pub fn draw_first_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
             ]
                 .as_ref(),
         )
-        .split(area);
+        .split(rows[0]);
 
     draw_list(f, app, chunks[0]);
     draw_planet_system_info(f, app, chunks[1], index);
+    draw_messages(f, app, rows[1]);
 }
 
 pub fn draw_list<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
@@ -64,34 +90,160 @@ pub fn draw_planet_system_info<B>(f: &mut Frame<B>, app: &mut App, area: Rect, i
         B: Backend,
 {
     let planet_system = app.planet_systems[index].clone();
+    let units = app.unit_system.body_units();
 
     let mut text = vec![
         // Line::from(index.to_string()),
         Line::from(planet_system.clone().name),
-        Line::from(vec![
-            Span::from("- Center star: "),
-            Span::from(planet_system.center_star.name.clone().to_string())
-        ]),
-        Line::from(vec![
-            Span::from(format!("- Num planets ({}): ", planet_system.planets.len().to_string())),
-        ]),
+        Line::from(format!("- Picture: {}", planet_system.picture_url)),
     ];
 
-    planet_system.planets.iter()
-        .for_each(|p| text.push(Line::from(format!("- - {}", p.name.clone()))));
+    for (i, star) in planet_system.stars.iter().enumerate() {
+        text.extend([
+            Line::from(vec![
+                Span::from(match i {
+                    0 => "- Center star: ",
+                    _ => "- Companion star: ",
+                }),
+                Span::from(star.name.clone())
+            ]),
+            Line::from(format!("- - Mass: {}", units.display("mass", star.mass))),
+            Line::from(format!("- - Radius: {}", units.display("radius", star.radius))),
+            Line::from(format!("- - Temperature: {}", units.display("effective_temperature", star.effective_temperature))),
+        ]);
+
+        // Derived properties can not be edited, so they are greyed out
+        derived_rows(star, vec![]).into_iter()
+            .for_each(|field| text.push(Line::from(Span::styled(format!("- - {}", field), Style::default().fg(Color::DarkGray)))));
+
+        if let Some(zone) = star.habitable_zone() {
+            text.push(habitable_zone(&zone, &units));
+        }
+
+        text.extend([
+            Line::from(format!("- - Picture: {}", star.picture_url)),
+            Line::from(format!("- - Source: {}", star.provenance)),
+        ]);
+    }
+
+    // Planets orbiting every star of the system orbit their barycentre
+    if planet_system.stars.len() > 1 {
+        let barycentre = CenterStar::barycentre(&planet_system.stars);
+
+        text.push(Line::from(format!("- Barycentre: {}", units.display("mass", barycentre.mass))));
+
+        if let Some(zone) = barycentre.habitable_zone() {
+            text.push(habitable_zone(&zone, &units));
+        }
+    }
+
+    let moon_count: usize = planet_system.planets.iter().map(|p| p.all_moons().len()).sum();
+
+    text.push(Line::from(format!("- Orbits ({} planets, {} moons):", planet_system.planets.len(), moon_count)));
+
+    for tree in planet_system.hierarchy() {
+        tree_lines(&tree, "  ", "", "  ", &planet_system, &units, &mut text);
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(
-            "System info",
+            format!("System info ({} units)", app.unit_system.name()),
             Style::default()
         ));
 
     let paragraph = Paragraph::new(text)
         .block(block)
         .wrap(Wrap {
-            trim: true
+            // The tree is indented with spaces
+            trim: false
         });
 
     f.render_widget(paragraph, area);
 }
+
+/// Lines of `tree`, the name of its body after `prefix` and `connector`, and its satellites below it, indented by
+/// `child_prefix`.
+fn tree_lines(
+    tree: &Tree<Member>,
+    prefix: &str,
+    connector: &str,
+    child_prefix: &str,
+    planet_system: &PlanetSystem,
+    units: &BodyUnits,
+    text: &mut Vec<Line<'static>>,
+) {
+    let mut name = vec![Span::from(format!("{}{}{}", prefix, connector, tree.body.name()))];
+
+    // The details of a body are indented like its satellites, with the line to the next satellite drawn through them
+    let details_prefix = match tree.satellites.is_empty() {
+        true => format!("{}   ", child_prefix),
+        false => format!("{}│  ", child_prefix),
+    };
+    let mut details: Vec<Line<'static>> = vec![];
+
+    match tree.body {
+        Member::Star(_) | Member::Barycentre => {}
+        Member::Planet(p) => {
+            let host = planet_system.host(p);
+
+            match &host {
+                Some(star) => if let Some(zone) = p.habitable_zone(star) {
+                    name.push(Span::styled(format!(" {}", zone.marker()), Style::default().fg(Color::Green)));
+                },
+                None => name.push(Span::styled(format!(" (orbits unknown star '{}')", p.orbits), Style::default().fg(Color::Red))),
+            }
+
+            details.push(Line::from(format!("{}{}", details_prefix, orbit_details(p, units))));
+
+            details.push(Line::from(Span::styled(
+                format!("{}{}", details_prefix, derived_rows(p, host.map(|star| p.orbit_fields(&star)).unwrap_or_default()).join(", ")),
+                Style::default().fg(Color::DarkGray)
+            )));
+        }
+        Member::Moon(m) => details.push(Line::from(format!("{}{}", details_prefix, orbit_details(m, units)))),
+    }
+
+    text.push(Line::from(name));
+    text.extend(details);
+
+    for (i, satellite) in tree.satellites.iter().enumerate() {
+        let (connector, indent) = match i + 1 == tree.satellites.len() {
+            true => ("└─ ", "   "),
+            false => ("├─ ", "│  "),
+        };
+
+        tree_lines(satellite, child_prefix, connector, &format!("{}{}", child_prefix, indent), planet_system, units, text);
+    }
+}
+
+/// Mass, radius and orbit of `body` on one line.
+fn orbit_details(body: &dyn OrbitingBody, units: &BodyUnits) -> String {
+    format!("{}, {}, a = {}, P = {}",
+        units.display("mass", body.mass().map(|m| m.get(BodyUnits::MODEL.mass))),
+        units.display("radius", body.radius().map(|r| r.get(BodyUnits::MODEL.radius))),
+        inferred(body, "semi_major_axis", units.display("semi_major_axis", body.semi_major_axis().map(|a| a.get(BodyUnits::MODEL.semi_major_axis)))),
+        inferred(body, "orbital_period", units.display("orbital_period", body.orbital_period().map(|p| p.get(BodyUnits::MODEL.orbital_period)))),
+    )
+}
+
+/// Greyed out line with the conservative and optimistic habitable zone of a star.
+fn habitable_zone(zone: &HabitableZone, units: &BodyUnits) -> Line<'static> {
+    let range = |r: &Range<Length>| format!("{} – {}",
+        units.display("semi_major_axis", Some(r.start.get(BodyUnits::MODEL.semi_major_axis))),
+        units.display("semi_major_axis", Some(r.end.get(BodyUnits::MODEL.semi_major_axis))),
+    );
+
+    Line::from(Span::styled(
+        format!("- - habitable_zone: {} (optimistic {})", range(&zone.conservative), range(&zone.optimistic)),
+        Style::default().fg(Color::DarkGray)
+    ))
+}
+
+/// `value` of `field`, marked if it was computed instead of measured.
+fn inferred(body: &dyn OrbitingBody, field: &str, value: String) -> String {
+    match body.inferred().field(field) {
+        true => format!("{} (inferred)", value),
+        false => value,
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    cmp::Ordering,
+    str::FromStr,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::planet_system::{
+    center_star::CenterStar,
+    csv_columns::CsvColumns,
+    epoch::Epoch,
+    file_format::FileFormat,
+    habitable_zone::Zone,
+    load_error::LoadOptions,
+    planet_system::PlanetSystem,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "hiof-oop-rust", version, about = "Browse and edit catalogues of planet systems")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Catalogue files to open, planet systems from all files are shown together
+    #[arg(default_value = "files/csv/planets_100.csv")]
+    pub files: Vec<String>,
+
+    /// Format of the files, guessed from the extension and header of each file if not given
+    #[arg(long, value_enum)]
+    pub format: Option<FormatArg>,
+
+    /// Milliseconds between each update of the user interface
+    #[arg(long, default_value_t = 250, value_parser = clap::value_parser!(u64).range(1..))]
+    pub tick_rate: u64,
+
+    /// Order of the planet systems in the list
+    #[arg(long, value_enum, default_value_t = SortBy::Name)]
+    pub sort_by: SortBy,
+
+    /// Turn off enhanced graphics in the user interface
+    #[arg(long)]
+    pub no_enhanced_graphics: bool,
+    #[command(flatten)]
+    pub load: LoadArgs,
+}
+
+/// Options for reading catalogue files, shared by the user interface and every command.
+#[derive(Debug, Clone, clap::Args)]
+pub struct LoadArgs {
+    /// Name of the planet system in solar CSV files, which do not name it. The name of the file if not given
+    #[arg(long)]
+    pub system_name: Option<String>,
+
+    /// Another header name for a column of CSV files, like "pl_mass=planet_mass". May be given more than once
+    #[arg(long)]
+    pub column_alias: Vec<ColumnAliasArg>,
+}
+
+impl LoadArgs {
+    pub fn options(&self, skip_bad_rows: bool) -> LoadOptions {
+        LoadOptions {
+            skip_bad_rows,
+            system_name: self.system_name.clone(),
+            column_aliases: self.column_alias.iter().map(|a| (a.column.clone(), a.alias.clone())).collect(),
+        }
+    }
+}
+
+/// Header name for a column of CSV files, parsed from "column=alias".
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnAliasArg {
+    pub column: String,
+    pub alias: String,
+}
+
+impl FromStr for ColumnAliasArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (column, alias) = s.split_once('=').ok_or_else(|| format!("Expected COLUMN=ALIAS, got '{}'", s))?;
+
+        if CsvColumns::planet_systems().column(column).is_none() && CsvColumns::solar().column(column).is_none() {
+            return Err(format!("Unknown column '{}'", column));
+        }
+
+        if alias.trim().is_empty() {
+            return Err(format!("Missing alias for '{}'", column));
+        }
+
+        Ok(ColumnAliasArg {
+            column: column.trim().to_string(),
+            alias: alias.trim().to_string(),
+        })
+    }
+}
+
+/// Commands run without starting the user interface.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the planet systems, center stars, planets or moons in catalogue files
+    Query(QueryArgs),
+    /// Read a catalogue file and write its planet systems to a file in another format
+    Convert(ConvertArgs),
+    /// Check catalogue files for impossible or missing values, failing if any errors are found
+    Validate(ValidateArgs),
+    /// Print where every planet is on its orbit at a given time, by Kepler's equation
+    Positions(PositionsArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct QueryArgs {
+    /// Catalogue files to read
+    #[arg(required = true)]
+    pub files: Vec<String>,
+
+    /// Format of the files, guessed from the extension and header of each file if not given
+    #[arg(long, value_enum)]
+    pub input_format: Option<FormatArg>,
+
+    /// Only planet systems with a name containing this text, ignoring case
+    #[arg(long)]
+    pub system: Option<String>,
+
+    /// Only planets with a name containing this text, ignoring case, and the systems and moons of these planets
+    #[arg(long)]
+    pub planet: Option<String>,
+
+    /// What to print a row for
+    #[arg(long, value_enum, default_value_t = ListArg::Planets)]
+    pub list: ListArg,
+
+    /// Comma separated columns to print, all columns if not given
+    #[arg(long, value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+
+    /// Order of the planet systems
+    #[arg(long, value_enum, default_value_t = SortBy::Name)]
+    pub sort_by: SortBy,
+
+    /// Only planets in the habitable zone of their star, and the systems and moons of these planets
+    #[arg(long, value_enum)]
+    pub habitable_zone: Option<HabitableZoneArg>,
+
+    /// Compute missing semi-major axes and orbital periods from the other by Kepler's third law
+    #[arg(long)]
+    pub fill_orbits: bool,
+    #[command(flatten)]
+    pub load: LoadArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConvertArgs {
+    /// Catalogue file to read
+    pub input: String,
+
+    /// File to write, replaced if it exists
+    pub output: String,
+
+    /// Format of the input file, guessed from its extension and header if not given
+    #[arg(long, value_enum)]
+    pub from: Option<FormatArg>,
+
+    /// Format of the output file, JSON for `.json` files and CSV for all other files if not given
+    #[arg(long, value_enum)]
+    pub to: Option<FormatArg>,
+
+    /// Leave out rows that cannot be read instead of stopping at the first one
+    #[arg(long)]
+    pub skip_bad_rows: bool,
+
+    /// Compute missing semi-major axes and orbital periods from the other by Kepler's third law before writing
+    #[arg(long)]
+    pub fill_orbits: bool,
+    #[command(flatten)]
+    pub load: LoadArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ValidateArgs {
+    /// Catalogue files to check, each file on its own
+    #[arg(required = true)]
+    pub files: Vec<String>,
+
+    /// Format of the files, guessed from the extension and header of each file if not given
+    #[arg(long, value_enum)]
+    pub input_format: Option<FormatArg>,
+
+    /// How far off an orbital period may be from the period given by its semi-major axis, relative to that period
+    #[arg(long, default_value_t = 0.1)]
+    pub period_tolerance: f64,
+
+    /// Fail on warnings as well as errors
+    #[arg(long)]
+    pub deny_warnings: bool,
+    #[command(flatten)]
+    pub load: LoadArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct PositionsArgs {
+    /// Catalogue files to read
+    #[arg(required = true)]
+    pub files: Vec<String>,
+
+    /// Format of the files, guessed from the extension and header of each file if not given
+    #[arg(long, value_enum)]
+    pub input_format: Option<FormatArg>,
+
+    /// Only planet systems with a name containing this text, ignoring case
+    #[arg(long)]
+    pub system: Option<String>,
+
+    /// Only planets with a name containing this text, ignoring case, and the moons of these planets
+    #[arg(long)]
+    pub planet: Option<String>,
+
+    /// Time to find the positions at: a date like 2024-03-20, a date and time like 2024-03-20T03:06, a Julian day like
+    /// JD2460389.5, J2000 or now
+    #[arg(long, default_value = "now")]
+    pub at: Epoch,
+
+    /// Time the mean anomalies are given for
+    #[arg(long, default_value = "J2000")]
+    pub epoch: Epoch,
+
+    /// Mean anomaly in degrees of every body at the epoch, 0 meaning at its periapsis
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    pub mean_anomaly: f64,
+
+    /// Mean anomaly in degrees of one body at the epoch, like "Earth=357.5", used instead of --mean-anomaly for that
+    /// body. May be given more than once
+    #[arg(long)]
+    pub mean_anomaly_of: Vec<MeanAnomalyArg>,
+
+    /// Also print the positions of moons, relative to their planet
+    #[arg(long)]
+    pub moons: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    #[command(flatten)]
+    pub load: LoadArgs,
+}
+
+impl PositionsArgs {
+    /// Mean anomaly in degrees at the epoch of the body named `body`.
+    pub fn mean_anomaly(&self, body: &str) -> f64 {
+        self.mean_anomaly_of.iter()
+            .rev()
+            .find(|m| m.body.eq_ignore_ascii_case(body))
+            .map(|m| m.degrees)
+            .unwrap_or(self.mean_anomaly)
+    }
+}
+
+/// Mean anomaly of a single body, parsed from "name=degrees".
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeanAnomalyArg {
+    pub body: String,
+    pub degrees: f64,
+}
+
+impl FromStr for MeanAnomalyArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (body, degrees) = s.rsplit_once('=').ok_or_else(|| format!("Expected NAME=DEGREES, got '{}'", s))?;
+
+        Ok(MeanAnomalyArg {
+            body: body.trim().to_string(),
+            degrees: degrees.trim().parse().map_err(|_| format!("Invalid mean anomaly '{}' for '{}'", degrees, body))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ListArg {
+    Systems,
+    Stars,
+    Planets,
+    Moons,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum HabitableZoneArg {
+    /// Between the runaway and maximum greenhouse limits
+    Conservative,
+    /// Between the recent Venus and early Mars limits, which includes the conservative zone
+    Optimistic,
+}
+
+impl HabitableZoneArg {
+    pub fn contains(&self, zone: Option<Zone>) -> bool {
+        matches!(
+            (self, zone),
+            (HabitableZoneArg::Conservative, Some(Zone::Conservative)) | (HabitableZoneArg::Optimistic, Some(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Columns aligned for reading in a terminal
+    Table,
+    /// One object per row
+    Json,
+    /// Header line followed by one line per row
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum FormatArg {
+    /// One planet per line, with its planet system and center star
+    Csv,
+    /// One sun, planet or moon per line, with the body it orbits
+    Solar,
+    Json,
+}
+
+impl From<FormatArg> for FileFormat {
+    fn from(value: FormatArg) -> Self {
+        match value {
+            FormatArg::Csv => FileFormat::Csv,
+            FormatArg::Solar => FileFormat::SolarCsv,
+            FormatArg::Json => FileFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum SortBy {
+    #[default]
+    Name,
+    /// Number of planets, most first
+    Planets,
+    StarName,
+    StarMass,
+    StarRadius,
+    StarTemperature,
+}
+
+impl SortBy {
+    pub fn sort(&self, planet_systems: &mut [PlanetSystem]) {
+        match self {
+            SortBy::Name => planet_systems.sort_by_cached_key(|ps| ps.name.clone()),
+            SortBy::Planets => planet_systems.sort_by_key(|ps| std::cmp::Reverse(ps.planets.len())),
+            SortBy::StarName => planet_systems.sort_by_cached_key(|ps| primary_star(ps, |s| s.name.clone())),
+            SortBy::StarMass => planet_systems.sort_by(|a, b| measurements(primary_star(a, |s| s.mass), primary_star(b, |s| s.mass))),
+            SortBy::StarRadius => planet_systems.sort_by(|a, b| measurements(primary_star(a, |s| s.radius), primary_star(b, |s| s.radius))),
+            SortBy::StarTemperature => planet_systems.sort_by(|a, b| {
+                measurements(primary_star(a, |s| s.effective_temperature), primary_star(b, |s| s.effective_temperature))
+            }),
+        }
+    }
+}
+
+/// Orders measurements from low to high, with the values that are not known last.
+fn measurements(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// `value` of the primary star of `planet_system`, systems are sorted by their primary star.
+fn primary_star<T: Default>(planet_system: &PlanetSystem, value: impl Fn(&CenterStar) -> T) -> T {
+    planet_system.stars.first().map(value).unwrap_or_default()
+}
@@ -0,0 +1,35 @@
+use std::error::Error;
+
+use crate::{
+    cli::args::ConvertArgs,
+    planet_system::{
+        consistency,
+        file_format::FileFormat,
+        planet_system::PlanetSystem,
+    },
+};
+
+/// Reads the input file of `args` and writes its planet systems to the output file, in the order they were read.
+pub fn convert(args: &ConvertArgs) -> Result<(), Box<dyn Error>> {
+    let from = args.from.map(FileFormat::from).unwrap_or_else(|| FileFormat::from_file(&args.input, &args.load.options(args.skip_bad_rows)));
+    let to = args.to.map(FileFormat::from).unwrap_or_else(|| FileFormat::from_extension(&args.output));
+
+    let mut report = PlanetSystem::load_systems(&args.input, from, &args.load.options(args.skip_bad_rows))?;
+
+    for warning in &report.warnings {
+        eprintln!("Skipped {}", warning);
+    }
+
+    if args.fill_orbits {
+        eprintln!("{}", consistency::fill_missing_orbits(&mut report.planet_systems));
+    }
+
+    PlanetSystem::save_systems(&report.planet_systems, args.output.clone(), to)?;
+
+    println!(
+        "Converted {} planet systems from '{}' ({:?}) to '{}' ({:?})",
+        report.planet_systems.len(), args.input, from, args.output, to
+    );
+
+    Ok(())
+}
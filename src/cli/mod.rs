@@ -0,0 +1,50 @@
+pub mod args;
+pub mod convert;
+pub mod positions;
+pub mod query;
+pub mod validate;
+
+use std::{
+    error::Error,
+    fmt,
+};
+
+use crate::planet_system::{
+    file_format::FileFormat,
+    load_error::{LoadError, LoadOptions, LoadReport},
+    planet_system::PlanetSystem,
+};
+
+/// Loads every file into a single report, guessing the format of each file if `format` is not given.
+pub fn load_files(files: &[String], format: Option<FileFormat>, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+    let mut report = LoadReport::default();
+
+    for file_name in files {
+        let file_report = match format {
+            Some(format) => PlanetSystem::load_systems(file_name, format, options)?,
+            None => PlanetSystem::load_systems_from_file(file_name, options)?,
+        };
+
+        report.planet_systems.extend(file_report.planet_systems);
+        report.warnings.extend(file_report.warnings);
+    }
+
+    Ok(report)
+}
+
+/// Error for a command that failed for a reason other than a file that could not be loaded.
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Debug for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CliError {}
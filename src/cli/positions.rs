@@ -0,0 +1,80 @@
+use std::{
+    error::Error,
+    io::Write,
+};
+
+use crate::{
+    cli::{
+        args::PositionsArgs,
+        load_files,
+        query::{contains, Cell, Table},
+    },
+    planet_system::{
+        celestial_body::OrbitingBody,
+        file_format::FileFormat,
+        orbit::Orbit,
+    },
+};
+
+/// Writes the position and velocity of every planet, and of every moon if asked for, at the time given by `args`.
+/// Bodies without an orbit are left out, with a warning on stderr.
+pub fn positions(args: &PositionsArgs, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let report = load_files(&args.files, args.input_format.map(FileFormat::from), &args.load.options(true))?;
+
+    for warning in &report.warnings {
+        eprintln!("Skipped {}", warning);
+    }
+
+    let mut table = Table {
+        columns: [
+            "system", "body", "orbits", "x_km", "y_km", "distance_km", "vx_km_s", "vy_km_s", "speed_km_s",
+            "mean_anomaly_deg", "true_anomaly_deg",
+        ].iter().map(|c| c.to_string()).collect(),
+        rows: vec![],
+    };
+
+    let mut row = |system: &str, body: &str, orbits: &str, orbit: Option<Orbit>| match orbit {
+        Some(orbit) => {
+            let orbit = orbit.with_mean_anomaly(args.mean_anomaly(body).to_radians(), args.epoch);
+            let state = orbit.state(args.at);
+            let number = |value: f64| Cell::Number(value);
+
+            table.rows.push(vec![
+                Cell::Text(system.to_string()),
+                Cell::Text(body.to_string()),
+                Cell::Text(orbits.to_string()),
+                number(state.position.0),
+                number(state.position.1),
+                number(state.distance()),
+                number(state.velocity.0),
+                number(state.velocity.1),
+                number(state.speed()),
+                number(orbit.mean_anomaly(args.at).to_degrees()),
+                number(state.true_anomaly.to_degrees()),
+            ]);
+        }
+        None => eprintln!("No orbit for '{}' in '{}', it needs a semi-major axis or orbital period and an eccentricity below 1", body, system),
+    };
+
+    let planet_systems = report.planet_systems.iter()
+        .filter(|ps| contains(&args.system, &ps.name));
+
+    for planet_system in planet_systems {
+        for planet in planet_system.planets.iter().filter(|p| contains(&args.planet, &p.name)) {
+            match planet_system.host(planet) {
+                Some(star) => row(&planet_system.name, &planet.name, &star.name, planet.orbit(star.as_ref())),
+                None => eprintln!("No star '{}' for '{}' in '{}'", planet.orbits, planet.name, planet_system.name),
+            }
+
+            if args.moons {
+                for (central, moon) in planet.all_moons() {
+                    row(&planet_system.name, &moon.name, central.name(), moon.orbit(central));
+                }
+            }
+        }
+    }
+
+    eprintln!("Positions at {} (JD {})", args.at, args.at.julian_day());
+
+    table.write(args.format, out)
+}
@@ -0,0 +1,341 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Write},
+};
+
+use serde::{
+    ser::SerializeMap,
+    Serialize,
+    Serializer,
+};
+
+use crate::{
+    cli::{
+        args::{ListArg, OutputFormat, QueryArgs},
+        load_files,
+        CliError,
+    },
+    planet_system::{
+        celestial_body::CelestialBody,
+        center_star::CenterStar,
+        consistency,
+        file_format::FileFormat,
+        moon::Moon,
+        planet::Planet,
+        planet_system::PlanetSystem,
+        units::MEASUREMENTS,
+    },
+    util::{
+        csv::write_record,
+        json_formatter::CatalogueFormatter,
+        number_format::format_number,
+        ui::FieldEditable,
+    },
+};
+
+/// Loads the files of `args` and writes the rows that match its filters to `out`.
+/// Rows that could not be loaded are skipped, with a warning on stderr.
+pub fn query(args: &QueryArgs, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let report = load_files(&args.files, args.input_format.map(FileFormat::from), &args.load.options(true))?;
+
+    for warning in &report.warnings {
+        eprintln!("Skipped {}", warning);
+    }
+
+    let mut planet_systems = report.planet_systems;
+
+    if args.fill_orbits {
+        eprintln!("{}", consistency::fill_missing_orbits(&mut planet_systems));
+    }
+
+    if let Some(habitable_zone) = args.habitable_zone {
+        for planet_system in planet_systems.iter_mut() {
+            // `retain` visits the planets in order, so the zones can be found before the planets are borrowed mutably
+            let zones: Vec<bool> = planet_system.planets.iter()
+                .map(|p| habitable_zone.contains(planet_system.host(p).and_then(|star| p.habitable_zone(&star))))
+                .collect();
+            let mut zones = zones.into_iter();
+
+            planet_system.planets.retain(|_| zones.next().unwrap_or_default());
+        }
+
+        planet_systems.retain(|ps| !ps.planets.is_empty());
+    }
+
+    let mut planet_systems: Vec<PlanetSystem> = planet_systems.into_iter()
+        .filter(|ps| contains(&args.system, &ps.name))
+        .filter(|ps| args.planet.is_none() || ps.planets.iter().any(|p| contains(&args.planet, &p.name)))
+        .collect();
+
+    args.sort_by.sort(&mut planet_systems);
+
+    let table = Table::new(args.list, &planet_systems, &args.planet).select(&args.fields)?;
+
+    table.write(args.format, out)
+}
+
+pub fn contains(filter: &Option<String>, name: &str) -> bool {
+    match filter {
+        Some(filter) => name.to_lowercase().contains(&filter.to_lowercase()),
+        None => true,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Count(usize),
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cell::Text(text) => write!(f, "{}", text),
+            Cell::Number(number) => write!(f, "{}", format_number(*number)),
+            Cell::Count(count) => write!(f, "{}", count),
+        }
+    }
+}
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Cell::Text(text) => serializer.serialize_str(text),
+            Cell::Number(number) => serializer.serialize_f64(*number),
+            Cell::Count(count) => serializer.serialize_u64(*count as u64),
+        }
+    }
+}
+
+/// Column names of the editable fields of `T`.
+fn field_names<T: FieldEditable + Default>() -> Vec<String> {
+    T::default().get_fields().into_iter()
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Cells of the editable fields of `body`, with the measurements that are not known left empty.
+fn field_cells<T: FieldEditable + Default>(body: &T) -> Vec<Cell> {
+    body.get_fields().into_iter()
+        .zip(T::default().get_fields())
+        .map(|((name, value), (_, default))| match MEASUREMENTS.contains(&name) || default.parse::<f64>().is_ok() {
+            true => value.parse().map(Cell::Number).unwrap_or(Cell::Text(String::new())),
+            false => Cell::Text(value),
+        })
+        .collect()
+}
+
+/// Columns of the properties derived for every kind of body, see `derived_cells`.
+const DERIVED_COLUMNS: [&str; 3] = ["density", "surface_gravity", "escape_velocity"];
+
+fn derived_columns() -> Vec<String> {
+    DERIVED_COLUMNS.iter().map(|c| c.to_string()).collect()
+}
+
+/// Density in g/cm³, surface gravity in m/s² and escape velocity in km/s of `body`, left empty if they are not known.
+fn derived_cells(body: &dyn CelestialBody) -> Vec<Cell> {
+    [
+        body.density().map(|d| d / 1000.0),
+        body.surface_gravity(),
+        body.escape_velocity().map(|v| v / 1000.0),
+    ]
+        .into_iter()
+        .map(|value| value.map(Cell::Number).unwrap_or(Cell::Text(String::new())))
+        .collect()
+}
+
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// One row per planet system, star, planet or moon. Planets and moons are left out if the name of the
+    /// planet does not contain `planet`.
+    pub fn new(list: ListArg, planet_systems: &[PlanetSystem], planet: &Option<String>) -> Table {
+        let planets = || planet_systems.iter()
+            .flat_map(|ps| ps.planets.iter().map(move |p| (ps, p)))
+            .filter(|(_, p)| contains(planet, &p.name));
+
+        match list {
+            ListArg::Systems => Table {
+                columns: [field_names::<PlanetSystem>(), vec!["stars".to_string(), "planets".to_string()]].concat(),
+                rows: planet_systems.iter()
+                    .map(|ps| [
+                        field_cells(ps),
+                        vec![
+                            Cell::Text(ps.stars.iter().map(|s| s.name.as_str()).collect::<Vec<&str>>().join(", ")),
+                            Cell::Count(ps.planets.len()),
+                        ],
+                    ].concat())
+                    .collect(),
+            },
+            ListArg::Stars => Table {
+                columns: [vec!["system".to_string()], field_names::<CenterStar>(), derived_columns()].concat(),
+                rows: planet_systems.iter()
+                    .flat_map(|ps| ps.stars.iter().map(move |s| [
+                        vec![Cell::Text(ps.name.clone())],
+                        field_cells(s),
+                        derived_cells(s),
+                    ].concat()))
+                    .collect(),
+            },
+            ListArg::Planets => Table {
+                columns: [
+                    vec!["system".to_string()],
+                    field_names::<Planet>(),
+                    derived_columns(),
+                    vec!["moons".to_string(), "equilibrium_temperature".to_string(), "habitable_zone".to_string()],
+                ].concat(),
+                rows: planets()
+                    .map(|(ps, p)| {
+                        let host = ps.host(p);
+
+                        [
+                            vec![Cell::Text(ps.name.clone())],
+                            field_cells(p),
+                            derived_cells(p),
+                            vec![
+                                Cell::Count(p.moons.len()),
+                                // Left empty if the star or the orbit is not known
+                                host.as_ref()
+                                    .and_then(|star| p.equilibrium_temperature(star))
+                                    .map(|t| Cell::Number(t.round()))
+                                    .unwrap_or(Cell::Text(String::new())),
+                                Cell::Text(host.and_then(|star| p.habitable_zone(&star)).map(|z| z.name()).unwrap_or_default().to_string()),
+                            ],
+                        ].concat()
+                    })
+                    .collect(),
+            },
+            ListArg::Moons => Table {
+                columns: [
+                    vec!["system".to_string(), "planet".to_string(), "orbits".to_string()],
+                    field_names::<Moon>(),
+                    derived_columns(),
+                ].concat(),
+                // Moons of moons are listed with the planet they belong to, after the moon they orbit
+                rows: planets()
+                    .flat_map(|(ps, p)| p.all_moons().into_iter().map(move |(central, m)| [
+                        vec![Cell::Text(ps.name.clone()), Cell::Text(p.name.clone()), Cell::Text(central.name().to_string())],
+                        field_cells(m),
+                        derived_cells(m),
+                    ].concat()))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Keeps only `fields`, in the given order. All columns are kept if `fields` is empty.
+    pub fn select(self, fields: &[String]) -> Result<Table, CliError> {
+        if fields.is_empty() {
+            return Ok(self);
+        }
+
+        let indices = fields.iter()
+            .map(|field| self.columns.iter()
+                .position(|c| c.eq_ignore_ascii_case(field.trim()))
+                .ok_or_else(|| CliError(format!("Unknown field '{}', expected one of: {}", field, self.columns.join(", "))))
+            )
+            .collect::<Result<Vec<usize>, CliError>>()?;
+
+        Ok(Table {
+            columns: indices.iter().map(|i| self.columns[*i].clone()).collect(),
+            rows: self.rows.iter()
+                .map(|row| indices.iter().map(|i| row[*i].clone()).collect())
+                .collect(),
+        })
+    }
+
+    pub fn write(&self, format: OutputFormat, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let written = match format {
+            OutputFormat::Table => self.write_table(out),
+            OutputFormat::Json => self.write_json(out),
+            OutputFormat::Csv => self.write_csv(out),
+        }.and_then(|_| Ok(out.flush()?));
+
+        match written {
+            // The output was piped to a command that stopped reading, like `head`
+            Err(e) if e.downcast_ref::<io::Error>().map(|e| e.kind()) == Some(io::ErrorKind::BrokenPipe) => Ok(()),
+            written => written,
+        }
+    }
+
+    /// Writes the columns padded to the same width, with numbers aligned to the right.
+    pub fn write_table(&self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let rows: Vec<Vec<String>> = self.rows.iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+
+        let widths: Vec<usize> = self.columns.iter().enumerate()
+            .map(|(i, column)| rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or_default()
+            )
+            .collect();
+
+        let header: Vec<String> = self.columns.iter().zip(widths.iter())
+            .map(|(column, width)| format!("{:<width$}", column, width = width))
+            .collect();
+
+        writeln!(out, "{}", header.join("  ").trim_end())?;
+        writeln!(out, "{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("  "))?;
+
+        for (row, text) in self.rows.iter().zip(rows.iter()) {
+            let line: Vec<String> = row.iter().zip(text.iter()).zip(widths.iter())
+                .map(|((cell, text), width)| match cell {
+                    Cell::Text(_) => format!("{:<width$}", text, width = width),
+                    _ => format!("{:>width$}", text, width = width),
+                })
+                .collect();
+
+            writeln!(out, "{}", line.join("  ").trim_end())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_csv(&self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        writeln!(out, "{}", write_record(&self.columns))?;
+
+        for row in &self.rows {
+            writeln!(out, "{}", write_record(&row.iter().map(|cell| cell.to_string()).collect::<Vec<String>>()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes an array with an object per row, in the layout of the catalogue files.
+    pub fn write_json(&self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let rows: Vec<Row> = self.rows.iter()
+            .map(|cells| Row { columns: &self.columns, cells })
+            .collect();
+
+        let mut serializer = serde_json::Serializer::with_formatter(&mut *out, CatalogueFormatter::default());
+        rows.serialize(&mut serializer).map_err(io::Error::from)?;
+        writeln!(out)?;
+
+        Ok(())
+    }
+}
+
+struct Row<'a> {
+    columns: &'a [String],
+    cells: &'a [Cell],
+}
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.cells.len()))?;
+
+        for (column, cell) in self.columns.iter().zip(self.cells.iter()) {
+            map.serialize_entry(column, cell)?;
+        }
+
+        map.end()
+    }
+}
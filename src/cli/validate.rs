@@ -0,0 +1,60 @@
+use std::{
+    error::Error,
+    io::Write,
+};
+
+use crate::{
+    cli::{
+        args::ValidateArgs,
+        CliError,
+    },
+    planet_system::{
+        file_format::FileFormat,
+        planet_system::PlanetSystem,
+        validation::{self, Diagnostic, Severity, ValidateOptions},
+    },
+};
+
+/// Checks every file of `args` on its own and writes the diagnostics to `out`.
+/// Rows that can not be loaded are reported as errors, and fail the command like any other error.
+pub fn validate(args: &ValidateArgs, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+
+    for file_name in &args.files {
+        let format = args.input_format.map(FileFormat::from).unwrap_or_else(|| FileFormat::from_file(file_name, &args.load.options(true)));
+
+        let report = match PlanetSystem::load_systems(file_name, format, &args.load.options(true)) {
+            Ok(report) => report,
+            Err(e) => {
+                diagnostics.push(Diagnostic::from(e));
+                continue;
+            }
+        };
+
+        let options = ValidateOptions {
+            period_tolerance: args.period_tolerance,
+        };
+
+        let mut file_diagnostics: Vec<Diagnostic> = report.warnings.into_iter().map(Diagnostic::from).collect();
+        file_diagnostics.extend(validation::validate(&report.planet_systems, &options));
+        file_diagnostics.sort_by_key(|d| d.provenance.line);
+
+        diagnostics.extend(file_diagnostics);
+    }
+
+    for diagnostic in &diagnostics {
+        writeln!(out, "{}", diagnostic)?;
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    let warnings = diagnostics.len() - errors;
+
+    writeln!(out, "{} error(s), {} warning(s) in {} file(s)", errors, warnings, args.files.len())?;
+    out.flush()?;
+
+    if errors > 0 || (args.deny_warnings && warnings > 0) {
+        return Err(CliError(format!("Validation failed with {} error(s) and {} warning(s)", errors, warnings)).into());
+    }
+
+    Ok(())
+}
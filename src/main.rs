@@ -1,26 +1,60 @@
 #![allow(dead_code, unused_assignments, unused_imports, unused_variables)]
+#![allow(clippy::module_inception)]
 
 mod planet_system;
 mod app;
+mod cli;
 mod util;
 
 use {
     std::{
         error::Error,
+        io,
         time::Duration
     },
+    clap::Parser,
     crate::{
-        planet_system::planet_system::PlanetSystem,
+        planet_system::{
+            file_format::FileFormat,
+            load_error::LoadError,
+            planet_system::PlanetSystem,
+        },
         app::termion::run,
+        cli::{
+            args::{Args, Command},
+            convert::convert,
+            load_files,
+            positions::positions,
+            query::query,
+            validate::validate,
+        },
         util::ui::FieldEditable,
     }
 };
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut planet_systems = PlanetSystem::new_systems_from_file("files/csv/planets_100.csv".to_string());
-    planet_systems.sort_by_cached_key(|ps| ps.name.clone());
+    let args = Args::parse();
 
-    run(Duration::from_millis(250), true, planet_systems)?;
+    match &args.command {
+        Some(Command::Query(query_args)) => return query(query_args, &mut io::stdout().lock()),
+        Some(Command::Convert(convert_args)) => return convert(convert_args),
+        Some(Command::Validate(validate_args)) => return validate(validate_args, &mut io::stdout().lock()),
+        Some(Command::Positions(positions_args)) => return positions(positions_args, &mut io::stdout().lock()),
+        None => {}
+    }
+
+    let report = load_files(&args.files, args.format.map(FileFormat::from), &args.load.options(true))?;
+
+    if report.planet_systems.is_empty() {
+        return Err(LoadError::new("No planet systems").in_file(&args.files.join(", ")).into());
+    }
+
+    let mut planet_systems = report.planet_systems;
+    args.sort_by.sort(&mut planet_systems);
+
+    let messages: Vec<String> = report.warnings.iter().map(|w| format!("Skipped {}", w)).collect();
+
+    run(Duration::from_millis(args.tick_rate), !args.no_enhanced_graphics, planet_systems, messages, args.load.options(true), args.sort_by)?;
 
     Ok(())
 }
@@ -0,0 +1,68 @@
+use crate::planet_system::{
+    consistency::Inferred,
+    orbit::Orbit,
+    physics,
+    provenance::Provenance,
+    units::{Length, Mass, Time},
+};
+
+/// Center star, planet or moon of a planet system, with values in the units of the model.
+pub trait CelestialBody {
+    fn name(&self) -> &str;
+
+    /// Kind of body, like "planet", used in messages
+    fn kind(&self) -> &'static str;
+
+    /// `None` if it is not known
+    fn mass(&self) -> Option<Mass>;
+
+    /// `None` if it is not known
+    fn radius(&self) -> Option<Length>;
+
+    fn provenance(&self) -> &Provenance;
+
+    /// Kind and name of the body, like "planet 'Earth'".
+    fn describe(&self) -> String {
+        format!("{} '{}'", self.kind(), self.name())
+    }
+
+    /// Bulk density in kg/m^3, see `physics::density`.
+    fn density(&self) -> Option<f64> {
+        physics::density(self.mass()?, self.radius()?)
+    }
+
+    fn surface_gravity(&self) -> Option<f64> {
+        physics::surface_gravity(self.mass()?, self.radius()?)
+    }
+
+    fn escape_velocity(&self) -> Option<f64> {
+        physics::escape_velocity(self.mass()?, self.radius()?)
+    }
+
+    /// Derived properties, formatted to be shown after the fields of the body. Density, surface gravity and escape
+    /// velocity for every body.
+    fn derived_fields(&self) -> Vec<(&'static str, String)> {
+        physics::body_fields(self)
+    }
+}
+
+/// Planet or moon, orbiting another body. Orbital elements are `None` if they are not known.
+pub trait OrbitingBody: CelestialBody {
+    fn semi_major_axis(&self) -> Option<Length>;
+
+    fn eccentricity(&self) -> Option<f64>;
+
+    fn orbital_period(&self) -> Option<Time>;
+
+    /// Which orbital elements were computed, see `consistency::fill_missing_orbits`.
+    fn inferred(&self) -> &Inferred;
+
+    fn inferred_mut(&mut self) -> &mut Inferred;
+
+    fn set_orbit(&mut self, semi_major_axis: Length, orbital_period: Time);
+
+    /// Orbit around `central`, see `Orbit::of_body`.
+    fn orbit(&self, central: &dyn CelestialBody) -> Option<Orbit> {
+        Orbit::of_body(self.semi_major_axis(), self.eccentricity(), self.orbital_period(), self.mass(), central.mass())
+    }
+}
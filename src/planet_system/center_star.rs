@@ -1,34 +1,106 @@
 use std::error::Error;
-use crate::util::ui::FieldEditable;
+use crate::{
+    planet_system::{
+        celestial_body::CelestialBody,
+        habitable_zone::HabitableZone,
+        physics,
+        provenance::Provenance,
+        units::{BodyUnits, Length, Mass},
+    },
+    util::ui::FieldEditable,
+};
+
+/// What planets orbiting every star of their system, like circumbinary planets, name as the body they orbit.
+pub const BARYCENTRE: &str = "barycentre";
 
 #[derive(Debug, Clone, Default, FieldEditable)]
 pub struct CenterStar {
     pub name: String,
-    pub mass: f32,
-    pub radius: f32,
-    pub effective_temperature: f32,
+    /// Measurements are `None` if they are not known
+    pub mass: Option<f64>,
+    pub radius: Option<f64>,
+    pub effective_temperature: Option<f64>,
+    pub picture_url: String,
+    pub provenance: Provenance,
 }
 
 impl CenterStar {
     pub(crate) fn new() -> CenterStar {
         CenterStar {
             name: "".to_string(),
-            mass: 0.0,
-            radius: 0.0,
-            effective_temperature: 0.0,
+            mass: None,
+            radius: None,
+            effective_temperature: None,
+            picture_url: "".to_string(),
+            provenance: Provenance::default(),
         }
     }
-}
-
 
+    /// Star standing in for the barycentre of `stars`, for planets orbiting all of them. It has their total mass, and
+    /// the effective temperature of the brightest star with the radius that gives their total luminosity, so the
+    /// habitable zone and equilibrium temperatures around it are those of the stars together. Totals are not known if
+    /// they are not known for one of the stars.
+    pub fn barycentre(stars: &[CenterStar]) -> CenterStar {
+        let luminosity: Option<f64> = stars.iter().map(|s| s.luminosity()).sum();
+        let effective_temperature = stars.iter()
+            .filter_map(|s| Some((s.luminosity()?, s.effective_temperature?)))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, t)| t);
 
-impl From<Vec<String>> for CenterStar {
-    fn from(value: Vec<String>) -> Self {
         CenterStar {
-            name: value[0].to_string(),
-            mass: value[1].parse::<f32>().unwrap_or_default(),
-            radius: value[2].parse::<f32>().unwrap_or_default(),
-            effective_temperature: value[8].parse::<f32>().unwrap_or_default(),
+            name: BARYCENTRE.to_string(),
+            mass: stars.iter().map(|s| s.mass).sum(),
+            radius: luminosity.zip(effective_temperature)
+                .and_then(|(l, t)| physics::radius_of_luminosity(l, t))
+                .map(|r| r.get(BodyUnits::MODEL.radius)),
+            effective_temperature,
+            ..CenterStar::new()
         }
     }
+
+    /// Luminosity in W, see `physics::luminosity`.
+    pub fn luminosity(&self) -> Option<f64> {
+        physics::luminosity(self.radius()?, self.effective_temperature?)
+    }
+
+    pub fn spectral_class(&self) -> Option<char> {
+        physics::spectral_class(self.effective_temperature?)
+    }
+
+    /// See `HabitableZone::of`.
+    pub fn habitable_zone(&self) -> Option<HabitableZone> {
+        HabitableZone::of(self)
+    }
+}
+
+impl CelestialBody for CenterStar {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &'static str {
+        "star"
+    }
+
+    fn mass(&self) -> Option<Mass> {
+        self.mass.map(|m| Mass::new(m, BodyUnits::MODEL.mass))
+    }
+
+    fn radius(&self) -> Option<Length> {
+        self.radius.map(|r| Length::new(r, BodyUnits::MODEL.radius))
+    }
+
+    fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
+
+    /// Density, surface gravity and escape velocity, followed by the luminosity and spectral class.
+    fn derived_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = physics::body_fields(self);
+
+        fields.push(("luminosity", physics::display(self.luminosity().map(|l| l / physics::SOLAR_LUMINOSITY), "L☉")));
+        fields.push(("spectral_class", self.spectral_class().map(String::from).unwrap_or("—".to_string())));
+
+        fields
+    }
 }
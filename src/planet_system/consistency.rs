@@ -0,0 +1,235 @@
+use std::{
+    f64::consts::PI,
+    fmt,
+};
+
+use crate::planet_system::{
+    celestial_body::{CelestialBody, OrbitingBody},
+    moon::Moon,
+    physics::G,
+    planet_system::PlanetSystem,
+    units::{Length, Mass, Time},
+};
+
+/// Orbital period of a body of `mass` orbiting a body of `central_mass`, by Kepler's third law.
+pub fn orbital_period(semi_major_axis: Length, mass: Option<Mass>, central_mass: Mass) -> Time {
+    Time::from_seconds(2.0 * PI * (semi_major_axis.m().powi(3) / gravitational_parameter(mass, central_mass)).sqrt())
+}
+
+/// Semi-major axis of a body of `mass` orbiting a body of `central_mass`, by Kepler's third law.
+pub fn semi_major_axis(orbital_period: Time, mass: Option<Mass>, central_mass: Mass) -> Length {
+    Length::from_m((gravitational_parameter(mass, central_mass) * (orbital_period.seconds() / (2.0 * PI)).powi(2)).cbrt())
+}
+
+/// G (M + m) in m^3 s^-2, leaving out the mass of the orbiting body if it is not known.
+fn gravitational_parameter(mass: Option<Mass>, central_mass: Mass) -> f64 {
+    G * (central_mass.kg() + mass.map(|m| m.kg()).unwrap_or_default().max(0.0))
+}
+
+/// Which orbital elements of a planet or moon were computed instead of read from a file or typed in.
+/// Files have no place for this, so values that are saved and loaded again are taken to be measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Inferred {
+    pub semi_major_axis: bool,
+    pub orbital_period: bool,
+}
+
+impl Inferred {
+    pub fn field(&self, field: &str) -> bool {
+        match field {
+            "semi_major_axis" => self.semi_major_axis,
+            "orbital_period" => self.orbital_period,
+            _ => false,
+        }
+    }
+
+    /// Marks `field` as measured, used when a new value is given for it.
+    pub fn clear(&mut self, field: &str) {
+        match field {
+            "semi_major_axis" => self.semi_major_axis = false,
+            "orbital_period" => self.orbital_period = false,
+            _ => {}
+        }
+    }
+}
+
+/// Number of values filled in by `fill_missing_orbits`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FillReport {
+    pub semi_major_axes: usize,
+    pub orbital_periods: usize,
+}
+
+impl fmt::Display for FillReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Filled in {} semi-major axes and {} orbital periods", self.semi_major_axes, self.orbital_periods)
+    }
+}
+
+/// Fills in the orbital elements (semi-major axis or orbital period) that are missing for a planet or moon by
+/// Kepler's third law, from the one that is known and the mass of the body it orbits. Filled in values are marked as
+/// inferred, and bodies missing both elements, or orbiting a body without a mass or a star that is not in their system,
+/// are left as they are.
+pub fn fill_missing_orbits(planet_systems: &mut [PlanetSystem]) -> FillReport {
+    let mut report = FillReport::default();
+
+    for planet_system in planet_systems.iter_mut() {
+        let host_masses: Vec<Option<Mass>> = planet_system.planets.iter()
+            .map(|p| planet_system.host(p).and_then(|star| star.mass()))
+            .collect();
+
+        for (planet, host_mass) in planet_system.planets.iter_mut().zip(host_masses) {
+            fill_orbit(planet, host_mass, &mut report);
+
+            // Moons are filled from the top, as the mass of the body each of them orbits is not filled in
+            let planet_mass = planet.mass();
+            let mut moons: Vec<(&mut Moon, Option<Mass>)> = planet.moons.iter_mut().map(|m| (m, planet_mass)).collect();
+
+            while let Some((moon, central_mass)) = moons.pop() {
+                fill_orbit(moon, central_mass, &mut report);
+
+                let moon_mass = moon.mass();
+                moons.extend(moon.moons.iter_mut().map(|m| (m, moon_mass)));
+            }
+        }
+    }
+
+    report
+}
+
+fn fill_orbit(body: &mut dyn OrbitingBody, central_mass: Option<Mass>, report: &mut FillReport) {
+    let (semi_major_axis, orbital_period, mass) = (body.semi_major_axis(), body.orbital_period(), body.mass());
+
+    let central_mass = match central_mass {
+        Some(central_mass) if central_mass.kg() > 0.0 => central_mass,
+        _ => return,
+    };
+
+    match (semi_major_axis, orbital_period) {
+        (None, Some(orbital_period)) => {
+            body.set_orbit(self::semi_major_axis(orbital_period, mass, central_mass), orbital_period);
+            body.inferred_mut().semi_major_axis = true;
+            report.semi_major_axes += 1;
+        }
+        (Some(semi_major_axis), None) => {
+            body.set_orbit(semi_major_axis, self::orbital_period(semi_major_axis, mass, central_mass));
+            body.inferred_mut().orbital_period = true;
+            report.orbital_periods += 1;
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::planet_system::{
+        center_star::CenterStar,
+        planet::Planet,
+        units::{LengthUnit, MassUnit, TimeUnit},
+    };
+
+    /// HAT-P-7 and HAT-P-7 b as in files/csv/planets.csv, with the semi-major axis left out
+    fn hat_p_7() -> PlanetSystem {
+        PlanetSystem {
+            name: "HAT-P-7_System".to_string(),
+            stars: vec![CenterStar { name: "HAT-P-7".to_string(), mass: Some(3.10e30), ..CenterStar::default() }],
+            planets: vec![Planet {
+                name: "HAT-P-7_b".to_string(),
+                mass: Some(3.49e27),
+                orbital_period: Some(2.20474),
+                ..Planet::default()
+            }],
+            ..PlanetSystem::default()
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64, relative: f64) {
+        assert!((actual - expected).abs() <= expected.abs() * relative, "{} is not within {} of {}", actual, relative, expected);
+    }
+
+    #[test]
+    fn semi_major_axis_of_earth() {
+        let a = semi_major_axis(Time::new(365.256, TimeUnit::Day), Some(Mass::new(5.972e24, MassUnit::Kilogram)), Mass::new(1.9885e30, MassUnit::Kilogram));
+
+        assert_close(a.km(), 1.496e8, 1e-3);
+    }
+
+    #[test]
+    fn orbital_period_is_inverse_of_semi_major_axis() {
+        let (mass, central_mass) = (Some(Mass::new(3.49e27, MassUnit::Kilogram)), Mass::new(3.10e30, MassUnit::Kilogram));
+        let period = orbital_period(Length::new(5.754e6, LengthUnit::Kilometre), mass, central_mass);
+
+        assert_close(semi_major_axis(period, mass, central_mass).km(), 5.754e6, 1e-12);
+    }
+
+    #[test]
+    fn fills_semi_major_axis_from_orbital_period() {
+        let mut planet_systems = vec![hat_p_7()];
+
+        let report = fill_missing_orbits(&mut planet_systems);
+        let planet = &planet_systems[0].planets[0];
+
+        assert_eq!(report, FillReport { semi_major_axes: 1, orbital_periods: 0 });
+        assert_close(planet.semi_major_axis.unwrap(), 5.754e6, 1e-3);
+        assert_eq!(planet.orbital_period, Some(2.20474));
+        assert_eq!(planet.inferred, Inferred { semi_major_axis: true, orbital_period: false });
+    }
+
+    #[test]
+    fn fills_orbital_period_from_semi_major_axis() {
+        let mut planet_systems = vec![hat_p_7()];
+        let planet = &mut planet_systems[0].planets[0];
+        planet.semi_major_axis = Some(5.754e6);
+        planet.orbital_period = None;
+
+        let report = fill_missing_orbits(&mut planet_systems);
+
+        assert_eq!(report, FillReport { semi_major_axes: 0, orbital_periods: 1 });
+        assert_close(planet_systems[0].planets[0].orbital_period.unwrap(), 2.20474, 1e-3);
+        assert!(planet_systems[0].planets[0].inferred.orbital_period);
+    }
+
+    #[test]
+    fn leaves_orbits_without_both_elements() {
+        let mut planet_systems = vec![hat_p_7()];
+        planet_systems[0].planets[0].orbital_period = None;
+
+        assert_eq!(fill_missing_orbits(&mut planet_systems), FillReport::default());
+        assert_eq!(planet_systems[0].planets[0].semi_major_axis, None);
+        assert_eq!(planet_systems[0].planets[0].orbital_period, None);
+    }
+
+    #[test]
+    fn leaves_orbits_around_stars_without_mass() {
+        let mut planet_systems = vec![hat_p_7()];
+        planet_systems[0].stars[0].mass = None;
+
+        assert_eq!(fill_missing_orbits(&mut planet_systems), FillReport::default());
+        assert_eq!(planet_systems[0].planets[0].semi_major_axis, None);
+    }
+
+    #[test]
+    fn leaves_orbits_around_stars_not_in_the_system() {
+        let mut planet_systems = vec![hat_p_7()];
+        planet_systems[0].planets[0].orbits = "HAT-P-8".to_string();
+
+        assert_eq!(fill_missing_orbits(&mut planet_systems), FillReport::default());
+    }
+
+    #[test]
+    fn fills_moons_from_the_mass_of_their_planet() {
+        let mut planet_systems = vec![hat_p_7()];
+        planet_systems[0].planets[0].moons.push(Moon {
+            name: "Moon".to_string(),
+            semi_major_axis: Some(384_400.0),
+            ..Moon::default()
+        });
+        planet_systems[0].planets[0].mass = Some(5.972e24);
+
+        fill_missing_orbits(&mut planet_systems);
+
+        assert_close(planet_systems[0].planets[0].moons[0].orbital_period.unwrap(), 27.45, 1e-2);
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    collections::HashMap,
+    fs,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use crate::{
+    planet_system::{
+        load_error::LoadError,
+        units,
+    },
+    util::csv::{parse_records, Record},
+};
+
+/// Column layout `PlanetSystemsCSV` is parsed from, the layout of files/csv/planets_100.csv. `pl_cbflag` is 1 for
+/// planets orbiting every star of their system.
+pub const PLANET_SYSTEM_COLUMNS: [&str; 15] = [
+    "psys_name", "psys_pictureurl",
+    "st_name", "st_mass", "st_radius", "st_efftemp", "st_pictureurl",
+    "pl_name", "pl_mass", "pl_radius", "pl_semimajor", "pl_ecc", "pl_orbper", "pl_pictureurl", "pl_cbflag",
+];
+
+/// Column layout `Types` is parsed from, the layout of files/csv/solarSystem.csv.
+pub const SOLAR_COLUMNS: [&str; 9] = [
+    "name", "mass", "radius", "semiMajorAxis", "eccentricity", "orbitalPeriod", "centralCelestialBody", "type", "effectiveTemp",
+];
+
+/// Cells of a row rearranged into the layout of a `CsvColumns`, together with the line the row starts on.
+pub type Row = (usize, Result<Vec<String>, LoadError>);
+
+/// Maps the header names used in CSV files to the columns the parsers expect.
+///
+/// Every column is matched by its own name and its aliases, ignoring case. Aliases for other files can be added with
+/// `with_configured_aliases`.
+/// Rows are rearranged into the layout of `columns` before they are parsed, so the order of the columns in a file
+/// does not matter and columns that are not known are ignored.
+#[derive(Debug, Clone)]
+pub struct CsvColumns {
+    pub columns: &'static [&'static str],
+    pub optional: &'static [&'static str],
+    pub aliases: HashMap<&'static str, Vec<String>>,
+}
+
+impl CsvColumns {
+    pub fn planet_systems() -> CsvColumns {
+        CsvColumns {
+            columns: &PLANET_SYSTEM_COLUMNS,
+            optional: &["psys_pictureurl", "st_pictureurl", "pl_pictureurl", "pl_cbflag"],
+            aliases: HashMap::new(),
+        }
+            .with_aliases("psys_name", &["#_psys_name", "system_name"])
+            .with_aliases("st_name", &["hostname", "star_name"])
+            .with_aliases("st_mass", &["star_mass"])
+            .with_aliases("st_radius", &["star_radius"])
+            .with_aliases("st_efftemp", &["st_teff", "star_efftemp"])
+            .with_aliases("pl_name", &["planet_name"])
+            .with_aliases("pl_mass", &["planet_mass"])
+            .with_aliases("pl_radius", &["planet_radius"])
+            .with_aliases("pl_semimajor", &["pl_orbsmax", "planet_semimajor"])
+            .with_aliases("pl_ecc", &["pl_orbeccen", "planet_ecc"])
+            .with_aliases("pl_orbper", &["planet_orbper"])
+            .with_aliases("pl_cbflag", &["cb_flag"])
+    }
+
+    pub fn solar() -> CsvColumns {
+        CsvColumns {
+            columns: &SOLAR_COLUMNS,
+            optional: &["effectiveTemp"],
+            aliases: HashMap::new(),
+        }
+            .with_aliases("semiMajorAxis", &["semi_major_axis"])
+            .with_aliases("orbitalPeriod", &["orbital_period"])
+            .with_aliases("centralCelestialBody", &["central_celestial_body", "orbits"])
+            .with_aliases("effectiveTemp", &["effective_temperature", "effectiveTemperature"])
+    }
+
+    pub fn with_alias(self, column: &'static str, alias: &str) -> CsvColumns {
+        self.with_aliases(column, &[alias])
+    }
+
+    /// Adds aliases given as (column, alias), see `LoadOptions::column_aliases`. Aliases for columns that are not in
+    /// this layout are left out, as they are for the columns of another layout.
+    pub fn with_configured_aliases(self, aliases: &[(String, String)]) -> CsvColumns {
+        aliases.iter().fold(self, |columns, (column, alias)| match columns.column(column) {
+            Some(column) => columns.with_alias(column, alias),
+            None => columns,
+        })
+    }
+
+    /// The column of this layout named `name`, ignoring case.
+    pub fn column(&self, name: &str) -> Option<&'static str> {
+        self.columns.iter()
+            .find(|column| column.eq_ignore_ascii_case(name.trim()))
+            .copied()
+    }
+
+    pub fn with_aliases(mut self, column: &'static str, aliases: &[&str]) -> CsvColumns {
+        self.aliases.entry(column)
+            .or_default()
+            .extend(aliases.iter().map(|a| a.to_string()));
+
+        self
+    }
+
+    fn matches(&self, column: &str, header_name: &str) -> bool {
+        let header_name = header_name.trim();
+
+        column.eq_ignore_ascii_case(header_name) || self.aliases.get(column)
+            .map(|aliases| aliases.iter().any(|a| a.eq_ignore_ascii_case(header_name)))
+            .unwrap_or_default()
+    }
+
+    /// Maps every column to its position in `header`. Fails with the required columns that are not in `header`.
+    pub fn map_header(&self, header: &[String]) -> Result<ColumnMap, LoadError> {
+        let indices: Vec<Option<usize>> = self.columns.iter()
+            .map(|column| header.iter().position(|h| self.matches(column, h)))
+            .collect();
+
+        let missing: Vec<&str> = self.columns.iter()
+            .zip(indices.iter())
+            .filter(|(column, index)| index.is_none() && !self.optional.contains(column))
+            .map(|(column, _)| *column)
+            .collect();
+
+        match missing.is_empty() {
+            true => Ok(ColumnMap { indices, widths: header.len()..=header.len() }),
+            false => Err(LoadError::new(format!("Header is missing the columns {}", missing.join(", ")))),
+        }
+    }
+
+    /// Reads the rows of a CSV file in the layout of `columns`, using the header of the file if it has one. Files
+    /// starting with a row of values have no header, and their columns are in the order of the layout.
+    /// Every row is returned with the line it starts on, or the error for a row with the wrong number of cells.
+    /// Empty lines are left out.
+    pub fn read_file(&self, file_name: &str) -> Result<Vec<Row>, LoadError> {
+        let text = fs::read_to_string(file_name)
+            .map_err(|e| LoadError::new(e.to_string()).in_file(file_name))?;
+
+        let mut rows: Vec<Record> = parse_records(&text)
+            .map_err(|(line, message)| LoadError::new(message).in_file(file_name).at_line(line))?
+            .into_iter()
+            .filter(|(_, row)| !(row.len() == 1 && row[0].is_empty()))
+            .collect();
+
+        let column_map = match rows.first() {
+            Some((line, header)) if is_header(header) => {
+                let column_map = self.map_header(header).map_err(|e| e.in_file(file_name).at_line(*line))?;
+                rows.remove(0);
+                column_map
+            }
+            _ => self.positional(),
+        };
+
+        Ok(rows.into_iter()
+            .map(|(line, row)| (line, column_map.canonical_row(&row)))
+            .collect())
+    }
+
+    /// Map for files without a header, where the columns are in the order of `columns`. Optional columns at the end
+    /// may be left out.
+    pub fn positional(&self) -> ColumnMap {
+        let required = self.columns.iter()
+            .rposition(|column| !self.optional.contains(column))
+            .map_or(0, |i| i + 1);
+
+        ColumnMap {
+            indices: (0..self.columns.len()).map(Some).collect(),
+            widths: required..=self.columns.len(),
+        }
+    }
+}
+
+/// Whether `row` is a header, which has no numbers where every row of values has some.
+fn is_header(row: &[String]) -> bool {
+    !row.iter().any(|cell| cell.trim().parse::<f64>().is_ok())
+}
+
+/// Position of every column of a `CsvColumns` layout in a specific file.
+#[derive(Debug, Clone)]
+pub struct ColumnMap {
+    pub indices: Vec<Option<usize>>,
+    /// Numbers of cells a row may have
+    pub widths: RangeInclusive<usize>,
+}
+
+impl ColumnMap {
+    /// Rearranges `row` into the layout the map was made for, with the cells of columns that are not in the file left
+    /// empty. Fails if `row` has more or fewer cells than the file has columns.
+    pub fn canonical_row(&self, row: &[String]) -> Result<Vec<String>, LoadError> {
+        if !self.widths.contains(&row.len()) {
+            let expected = match (self.widths.start(), self.widths.end()) {
+                (start, end) if start == end => start.to_string(),
+                (start, end) => format!("{} to {}", start, end),
+            };
+
+            return Err(LoadError::new(format!("Expected {} cells, found {}", expected, row.len())));
+        }
+
+        Ok(self.indices.iter()
+            .map(|index| index
+                .and_then(|i| row.get(i))
+                .cloned()
+                .unwrap_or_default()
+            )
+            .collect())
+    }
+}
+
+/// Parses the cell at `index` of a row in the layout of `columns`, `None` if it is empty.
+pub fn parse_optional_cell<T: FromStr>(row: &[String], columns: &[&str], index: usize) -> Result<Option<T>, LoadError> {
+    match row[index].trim().is_empty() {
+        true => Ok(None),
+        false => parse_cell(row, columns, index).map(Some),
+    }
+}
+
+/// Parses the measurement in the cell at `index` of a row in the layout of `columns`, see `units::measurement`.
+pub fn parse_measurement(row: &[String], columns: &[&str], index: usize) -> Result<Option<f64>, LoadError> {
+    parse_optional_cell(row, columns, index).map(units::measurement)
+}
+
+/// Parses the cell at `index` of a row in the layout of `columns`.
+pub fn parse_cell<T: FromStr>(row: &[String], columns: &[&str], index: usize) -> Result<T, LoadError> {
+    let value = row[index].trim();
+
+    value.parse::<T>()
+        .map_err(|_| LoadError::invalid_value(columns[index], value, if value.is_empty() {
+            "Missing value"
+        } else {
+            "Not a number"
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads `text` as the file `name` in the layout of `CsvColumns::solar`, giving every row or the error of the file
+    fn read(name: &str, text: &str) -> Result<Vec<Vec<String>>, LoadError> {
+        let file = std::env::temp_dir().join(format!("{}_{}.csv", name, std::process::id()));
+        fs::write(&file, text).unwrap();
+
+        let rows = CsvColumns::solar().read_file(&file.to_string_lossy());
+        fs::remove_file(file).unwrap();
+
+        rows.map(|rows| rows.into_iter().map(|(_, row)| row.unwrap()).collect())
+    }
+
+    #[test]
+    fn maps_columns_by_header() {
+        let rows = read("by_header", "type,name,mass,radius,semiMajorAxis,eccentricity,orbitalPeriod,orbits\nplanet,Earth,5.972E24,6371,1,0.017,365,Sun\n").unwrap();
+
+        assert_eq!(rows, vec![
+            ["Earth", "5.972E24", "6371", "1", "0.017", "365", "Sun", "planet", ""].map(String::from).to_vec(),
+        ]);
+    }
+
+    #[test]
+    fn reads_files_without_header_by_position() {
+        let rows = read("without_header", "Earth,5.972E24,6371,1,0.017,365,Sun,planet,\n").unwrap();
+
+        assert_eq!(rows[0][0], "Earth");
+        assert_eq!(rows[0][7], "planet");
+    }
+
+    #[test]
+    fn fails_on_header_missing_a_column() {
+        let error = read("missing_column", "name,mass,radius,semiMajorAxs,eccentricity,orbitalPeriod,centralCelestialBody,type\nEarth,5.972E24,6371,1,0.017,365,Sun,planet\n")
+            .unwrap_err();
+
+        assert_eq!(error.line, Some(1));
+        assert_eq!(error.message, "Header is missing the columns semiMajorAxis");
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::planet_system::units::Time;
+
+/// Julian day of J2000, noon on the first of January 2000.
+const J2000_JULIAN_DAY: f64 = 2451545.0;
+/// Days from the Unix epoch to J2000.
+const J2000_UNIX_DAYS: f64 = 10957.5;
+
+/// Instant in time, in days since J2000. Dates are taken to be in UTC, and the difference to the time scales used by
+/// astronomers, about a minute, is left out.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Epoch(f64);
+
+impl Epoch {
+    pub const J2000: Epoch = Epoch(0.0);
+
+    pub fn now() -> Epoch {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+
+        Epoch(seconds / 86400.0 - J2000_UNIX_DAYS)
+    }
+
+    pub fn from_julian_day(julian_day: f64) -> Epoch {
+        Epoch(julian_day - J2000_JULIAN_DAY)
+    }
+
+    pub fn julian_day(&self) -> f64 {
+        self.0 + J2000_JULIAN_DAY
+    }
+
+    /// Time from `earlier` to this epoch, negative if `earlier` is later.
+    pub fn since(&self, earlier: Epoch) -> Time {
+        Time::from_days(self.0 - earlier.0)
+    }
+
+    /// Epoch `time` after this one.
+    pub fn after(&self, time: Time) -> Epoch {
+        Epoch(self.0 + time.days())
+    }
+}
+
+/// Shown as a date and time in UTC, like "2023-06-01 12:00".
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unix_minutes = ((self.0 + J2000_UNIX_DAYS) * 1440.0).round() as i64;
+        let (year, month, day) = civil_from_days(unix_minutes.div_euclid(1440));
+        let minutes = unix_minutes.rem_euclid(1440);
+
+        write!(f, "{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, minutes / 60, minutes % 60)
+    }
+}
+
+/// Parses "now", "J2000", a Julian day like "JD2460000.5", or a date with an optional time like "2023-06-01" and
+/// "2023-06-01T18:30".
+impl FromStr for Epoch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("Invalid date '{}', expected YYYY-MM-DD, YYYY-MM-DDTHH:MM, JD<julian day>, J2000 or now", s);
+
+        if s.eq_ignore_ascii_case("now") {
+            return Ok(Epoch::now());
+        }
+
+        if s.eq_ignore_ascii_case("J2000") {
+            return Ok(Epoch::J2000);
+        }
+
+        if let Some(julian_day) = s.strip_prefix("JD").or_else(|| s.strip_prefix("jd")) {
+            return julian_day.trim().parse::<f64>().map(Epoch::from_julian_day).map_err(|_| invalid());
+        }
+
+        let (date, time) = s.split_once(['T', ' ']).unwrap_or((s, "00:00"));
+
+        let date: Vec<i64> = date.split('-').map(|p| p.parse::<i64>()).collect::<Result<_, _>>().map_err(|_| invalid())?;
+        let time: Vec<f64> = time.split(':').map(|p| p.parse::<f64>()).collect::<Result<_, _>>().map_err(|_| invalid())?;
+
+        match (date.as_slice(), time.as_slice()) {
+            (&[year, month @ 1..=12, day @ 1..=31], &[hours, minutes, ref seconds @ ..]) if seconds.len() <= 1 => {
+                let seconds = seconds.first().copied().unwrap_or_default();
+                let days = days_from_civil(year, month, day) as f64 + (hours * 3600.0 + minutes * 60.0 + seconds) / 86400.0;
+
+                Ok(Epoch(days - J2000_UNIX_DAYS))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Days from the Unix epoch to a date of the Gregorian calendar, by the algorithm of Howard Hinnant.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch(s: &str) -> Epoch {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_j2000_in_every_form() {
+        assert_eq!(epoch("J2000"), Epoch::J2000);
+        assert_eq!(epoch("JD2451545"), Epoch::J2000);
+        assert_eq!(epoch("2000-01-01T12:00"), Epoch::J2000);
+        assert_eq!(epoch("2000-01-01 12:00:00"), Epoch::J2000);
+    }
+
+    #[test]
+    fn parses_dates_without_time_as_midnight() {
+        assert_eq!(epoch("2000-01-01"), Epoch(-0.5));
+        assert_eq!(epoch("2023-06-01").julian_day(), 2460096.5);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for s in ["", "2023-13-01", "2023-06-32", "2023-06", "2023-06-01T18", "JDx", "yesterday"] {
+            assert!(s.parse::<Epoch>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn displays_dates_it_parses() {
+        for s in ["2000-01-01 12:00", "2023-06-01 18:30", "1969-07-20 20:17", "2400-02-29 00:00"] {
+            assert_eq!(epoch(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn time_between_epochs() {
+        let later = epoch("2000-01-11T12:00");
+
+        assert_eq!(later.since(Epoch::J2000).days(), 10.0);
+        assert_eq!(Epoch::J2000.after(Time::from_days(10.0)), later);
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use crate::{
+    planet_system::{
+        csv_columns::CsvColumns,
+        load_error::LoadOptions,
+    },
+    util::csv::parse_records,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    /// One planet per line, together with its planet system and center star (files/csv/planets_100.csv)
+    Csv,
+    /// One body per line, naming the body it orbits (files/csv/solarSystem.csv)
+    SolarCsv,
+    Json,
+}
+
+impl FileFormat {
+    /// Format to save a file in, JSON for `.json` files and CSV for all other files.
+    pub fn from_extension(file_name: &str) -> FileFormat {
+        if file_name.ends_with(".json") {
+            FileFormat::Json
+        } else {
+            FileFormat::Csv
+        }
+    }
+
+    /// Guesses the format from the file extension, CSV files are told apart by the columns in their header, which
+    /// may use the aliases of `options`.
+    pub fn from_file(file_name: &str, options: &LoadOptions) -> FileFormat {
+        if FileFormat::from_extension(file_name) == FileFormat::Json {
+            return FileFormat::Json;
+        }
+
+        let header: Vec<String> = File::open(file_name).ok()
+            .and_then(|file| BufReader::new(file).lines().next())
+            .and_then(|line| line.ok())
+            .and_then(|line| parse_records(&line).ok())
+            .and_then(|records| records.into_iter().next())
+            .map(|(_, record)| record)
+            .unwrap_or_default();
+
+        if CsvColumns::solar().with_configured_aliases(&options.column_aliases).map_header(&header).is_ok() {
+            FileFormat::SolarCsv
+        } else {
+            FileFormat::Csv
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::ops::Range;
+
+use crate::planet_system::{
+    center_star::CenterStar,
+    physics::SOLAR_LUMINOSITY,
+    units::{Length, LengthUnit},
+};
+
+/// Edges of the habitable zone of Kopparapu et al. (2014), for a planet of one Earth mass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// Inner edge of the optimistic zone, where Venus is thought to have lost its water
+    RecentVenus,
+    /// Inner edge of the conservative zone
+    RunawayGreenhouse,
+    /// Outer edge of the conservative zone
+    MaximumGreenhouse,
+    /// Outer edge of the optimistic zone, where Mars is thought to have had liquid water
+    EarlyMars,
+}
+
+impl Boundary {
+    /// Stellar flux at the boundary for a star like the sun, relative to the flux at the Earth, and the coefficients of
+    /// its change with the effective temperature of the star.
+    fn coefficients(&self) -> [f64; 5] {
+        match self {
+            Boundary::RecentVenus => [1.776, 2.136e-4, 2.533e-8, -1.332e-11, -3.097e-15],
+            Boundary::RunawayGreenhouse => [1.107, 1.332e-4, 1.580e-8, -8.308e-12, -1.931e-15],
+            Boundary::MaximumGreenhouse => [0.356, 6.171e-5, 1.698e-9, -3.198e-12, -5.575e-16],
+            Boundary::EarlyMars => [0.320, 5.547e-5, 1.526e-9, -2.874e-12, -5.011e-16],
+        }
+    }
+
+    /// Distance of the boundary from a star with `luminosity` in solar luminosities. The fit only holds from 2600 K to
+    /// 7200 K, so temperatures outside of it are moved to the closest end.
+    pub fn distance(&self, luminosity: f64, effective_temperature: f64) -> Length {
+        let t = effective_temperature.clamp(2600.0, 7200.0) - 5780.0;
+        let [s, a, b, c, d] = self.coefficients();
+        let flux = s + a * t + b * t.powi(2) + c * t.powi(3) + d * t.powi(4);
+
+        Length::new((luminosity / flux).sqrt(), LengthUnit::AstronomicalUnit)
+    }
+}
+
+/// Part of the habitable zone a planet orbits in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Conservative,
+    /// In the optimistic zone, but outside of the conservative zone
+    Optimistic,
+}
+
+impl Zone {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Zone::Conservative => "conservative",
+            Zone::Optimistic => "optimistic",
+        }
+    }
+
+    /// Marker shown next to planets in the habitable zone.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Zone::Conservative => "HZ",
+            Zone::Optimistic => "HZ (optimistic)",
+        }
+    }
+}
+
+/// Distances from a star where a planet like the Earth could have liquid water on its surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HabitableZone {
+    pub conservative: Range<Length>,
+    /// Wider than the conservative zone on both sides
+    pub optimistic: Range<Length>,
+}
+
+impl HabitableZone {
+    /// `None` if the luminosity of `star` is not known.
+    pub fn of(star: &CenterStar) -> Option<HabitableZone> {
+        let luminosity = star.luminosity()? / SOLAR_LUMINOSITY;
+        let effective_temperature = star.effective_temperature?;
+        let distance = |boundary: Boundary| boundary.distance(luminosity, effective_temperature);
+
+        Some(HabitableZone {
+            conservative: distance(Boundary::RunawayGreenhouse)..distance(Boundary::MaximumGreenhouse),
+            optimistic: distance(Boundary::RecentVenus)..distance(Boundary::EarlyMars),
+        })
+    }
+
+    /// Zone of a planet orbiting at `semi_major_axis`, `None` if it is outside of the habitable zone.
+    pub fn zone(&self, semi_major_axis: Length) -> Option<Zone> {
+        match semi_major_axis {
+            a if self.conservative.contains(&a) => Some(Zone::Conservative),
+            a if self.optimistic.contains(&a) => Some(Zone::Optimistic),
+            _ => None,
+        }
+    }
+}
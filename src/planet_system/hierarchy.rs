@@ -0,0 +1,322 @@
+use std::{
+    collections::HashMap,
+    fmt,
+};
+
+use crate::planet_system::{
+    center_star::{CenterStar, BARYCENTRE},
+    moon::Moon,
+    planet::Planet,
+};
+
+/// Body with the bodies orbiting it, which have satellites of their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tree<T> {
+    pub body: T,
+    pub satellites: Vec<Tree<T>>,
+}
+
+impl<T> Tree<T> {
+    pub fn leaf(body: T) -> Tree<T> {
+        Tree { body, satellites: vec![] }
+    }
+}
+
+/// Body read from a file, with the name of the body it orbits as given by its `centralCelestialBody`.
+#[derive(Debug, Clone)]
+pub struct Link<T> {
+    pub body: T,
+    pub name: String,
+    /// `None` for bodies that orbit nothing in the file, like stars
+    pub central: Option<String>,
+}
+
+/// Why a body could not be placed in the hierarchy.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkError {
+    /// No body has the name the body orbits
+    Dangling,
+    /// The body orbits itself, through the bodies named in order
+    Cycle(Vec<String>),
+    /// The body it orbits could not be placed
+    Orphan,
+    /// The body can not orbit the kind of body it orbits, like a planet orbiting a moon
+    Invalid(String),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkError::Dangling => write!(f, "No body with this name"),
+            LinkError::Cycle(names) => write!(f, "Orbits in a cycle: {}", names.join(" -> ")),
+            LinkError::Orphan => write!(f, "Orbits a body that could not be loaded"),
+            LinkError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Placement {
+    Unknown,
+    Placed,
+    Failed,
+}
+
+/// Nests every body under the body it orbits, by name. `accepts` is given the body a body orbits, `None` for bodies
+/// that orbit nothing, and returns why the body can not orbit it.
+///
+/// Returns the trees of the bodies that orbit nothing, and the index in `links` of every body that could not be
+/// placed with the reason. Bodies orbiting a name that no body has, or orbiting themselves through other bodies, can
+/// not be placed, and neither can the bodies orbiting them. Satellites are in the order of `links`, and names that
+/// are used more than once refer to the first body with the name.
+pub fn resolve<T>(links: Vec<Link<T>>, accepts: impl Fn(Option<&T>, &T) -> Result<(), String>) -> (Vec<Tree<T>>, Vec<(usize, LinkError)>) {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+
+    for (i, link) in links.iter().enumerate() {
+        index.entry(link.name.as_str()).or_insert(i);
+    }
+
+    let mut placement = vec![Placement::Unknown; links.len()];
+    let mut errors: Vec<(usize, LinkError)> = vec![];
+
+    for start in 0..links.len() {
+        // Follows the bodies orbited from `start`, until one is reached that orbits nothing or is already placed
+        let mut path: Vec<usize> = vec![];
+        let mut current = Some(start);
+
+        while let Some(i) = current {
+            if placement[i] != Placement::Unknown {
+                break;
+            }
+
+            if let Some(position) = path.iter().position(|p| *p == i) {
+                let cycle: Vec<String> = path[position..].iter()
+                    .chain([&i])
+                    .map(|p| links[*p].name.clone())
+                    .collect();
+
+                for p in path.drain(position..) {
+                    placement[p] = Placement::Failed;
+                    errors.push((p, LinkError::Cycle(cycle.clone())));
+                }
+
+                break;
+            }
+
+            path.push(i);
+
+            current = match &links[i].central {
+                Some(central) => match index.get(central.as_str()) {
+                    Some(central) => Some(*central),
+                    None => {
+                        path.pop();
+                        placement[i] = Placement::Failed;
+                        errors.push((i, LinkError::Dangling));
+                        None
+                    }
+                },
+                None => None,
+            };
+        }
+
+        // The bodies on the path are placed from the top, so the body each of them orbits is placed first
+        for i in path.into_iter().rev() {
+            let central = links[i].central.as_ref().and_then(|c| index.get(c.as_str())).copied();
+
+            let result = match central {
+                Some(central) if placement[central] != Placement::Placed => Err(LinkError::Orphan),
+                central => accepts(central.map(|c| &links[c].body), &links[i].body).map_err(LinkError::Invalid),
+            };
+
+            placement[i] = match result {
+                Ok(_) => Placement::Placed,
+                Err(e) => {
+                    errors.push((i, e));
+                    Placement::Failed
+                }
+            };
+        }
+    }
+
+    let mut satellites: Vec<Vec<usize>> = vec![vec![]; links.len()];
+    let mut roots: Vec<usize> = vec![];
+
+    for (i, link) in links.iter().enumerate().filter(|(i, _)| placement[*i] == Placement::Placed) {
+        match link.central.as_ref().and_then(|c| index.get(c.as_str())) {
+            Some(central) => satellites[*central].push(i),
+            None => roots.push(i),
+        }
+    }
+
+    let mut bodies: Vec<Option<T>> = links.into_iter().map(|l| Some(l.body)).collect();
+
+    errors.sort_by_key(|(i, _)| *i);
+
+    (roots.into_iter().map(|r| build(r, &mut bodies, &satellites)).collect(), errors)
+}
+
+/// Tree of the body at `i`, taking the bodies out of `bodies`.
+fn build<T>(i: usize, bodies: &mut [Option<T>], satellites: &[Vec<usize>]) -> Tree<T> {
+    Tree {
+        body: bodies[i].take().expect("every body is placed once"),
+        satellites: satellites[i].iter().map(|s| build(*s, bodies, satellites)).collect(),
+    }
+}
+
+/// Trees of `moons` and the moons orbiting them.
+pub fn moons(moons: &[Moon]) -> Vec<Tree<Member<'_>>> {
+    moons.iter()
+        .map(|m| Tree { body: Member::Moon(m), satellites: self::moons(&m.moons) })
+        .collect()
+}
+
+/// Body of a planet system, as placed in `PlanetSystem::hierarchy`.
+#[derive(Debug, Clone, Copy)]
+pub enum Member<'a> {
+    Star(&'a CenterStar),
+    /// Barycentre of every star, orbited by the planets that orbit all of them
+    Barycentre,
+    Planet(&'a Planet),
+    Moon(&'a Moon),
+}
+
+impl Member<'_> {
+    pub fn name(&self) -> &str {
+        match self {
+            Member::Star(star) => &star.name,
+            Member::Barycentre => BARYCENTRE,
+            Member::Planet(planet) => &planet.name,
+            Member::Moon(moon) => &moon.name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bodies are their kind, "sun", "planet" or "moon"
+    fn link(name: &str, central: Option<&str>, kind: &'static str) -> Link<&'static str> {
+        Link { body: kind, name: name.to_string(), central: central.map(String::from) }
+    }
+
+    fn accepts(central: Option<&&str>, body: &&str) -> Result<(), String> {
+        match (central.copied(), *body) {
+            (None, "sun") | (Some("sun"), "planet") | (Some("planet" | "moon"), "moon") => Ok(()),
+            (central, body) => Err(format!("A {} can not orbit {:?}", body, central)),
+        }
+    }
+
+    #[test]
+    fn nests_moons_of_moons() {
+        let (trees, errors) = resolve(vec![
+            link("Minimoon", Some("Moon"), "moon"),
+            link("Sun", None, "sun"),
+            link("Moon", Some("Earth"), "moon"),
+            link("Earth", Some("Sun"), "planet"),
+            link("Mars", Some("Sun"), "planet"),
+        ], accepts);
+
+        assert_eq!(errors, vec![]);
+        assert_eq!(trees, vec![Tree {
+            body: "sun",
+            satellites: vec![
+                Tree { body: "planet", satellites: vec![
+                    Tree { body: "moon", satellites: vec![Tree::leaf("moon")] },
+                ] },
+                Tree::leaf("planet"),
+            ],
+        }]);
+    }
+
+    #[test]
+    fn keeps_satellites_in_order_of_links() {
+        // Bodies are their names
+        let links = ["Sun", "Phobos", "Mars", "Deimos"].iter()
+            .zip([None, Some("Mars"), Some("Sun"), Some("Mars")])
+            .map(|(name, central)| Link { body: *name, name: name.to_string(), central: central.map(String::from) })
+            .collect();
+
+        let (trees, _) = resolve(links, |_, _| Ok(()));
+
+        assert_eq!(trees[0].satellites[0].satellites, vec![Tree::leaf("Phobos"), Tree::leaf("Deimos")]);
+    }
+
+    #[test]
+    fn fails_bodies_orbiting_unknown_names() {
+        let (trees, errors) = resolve(vec![
+            link("Sun", None, "sun"),
+            link("Vulcan", Some("Nemesis"), "planet"),
+        ], accepts);
+
+        assert_eq!(errors, vec![(1, LinkError::Dangling)]);
+        assert_eq!(trees, vec![Tree::leaf("sun")]);
+    }
+
+    #[test]
+    fn fails_bodies_in_a_cycle() {
+        let (trees, errors) = resolve(vec![
+            link("A", Some("B"), "moon"),
+            link("B", Some("A"), "moon"),
+        ], accepts);
+
+        let cycle = LinkError::Cycle(vec!["A".to_string(), "B".to_string(), "A".to_string()]);
+
+        assert_eq!(errors, vec![(0, cycle.clone()), (1, cycle)]);
+        assert_eq!(trees, vec![]);
+    }
+
+    #[test]
+    fn fails_bodies_orbiting_themselves() {
+        let (_, errors) = resolve(vec![link("A", Some("A"), "moon")], accepts);
+
+        assert_eq!(errors, vec![(0, LinkError::Cycle(vec!["A".to_string(), "A".to_string()]))]);
+    }
+
+    #[test]
+    fn fails_bodies_orbiting_bodies_that_failed() {
+        let (trees, errors) = resolve(vec![
+            link("Sun", None, "sun"),
+            link("Vulcan", Some("Nemesis"), "planet"),
+            link("Vulcan I", Some("Vulcan"), "moon"),
+            link("A", Some("B"), "moon"),
+            link("B", Some("A"), "moon"),
+            link("C", Some("A"), "moon"),
+            link("C I", Some("C"), "moon"),
+        ], accepts);
+
+        assert_eq!(errors.iter().map(|(i, e)| (*i, matches!(e, LinkError::Orphan))).collect::<Vec<_>>(), vec![
+            (1, false), (2, true), (3, false), (4, false), (5, true), (6, true),
+        ]);
+        assert_eq!(trees, vec![Tree::leaf("sun")]);
+    }
+
+    #[test]
+    fn fails_bodies_that_can_not_orbit_their_central_body() {
+        let (trees, errors) = resolve(vec![
+            link("Sun", None, "sun"),
+            link("Moon", Some("Sun"), "moon"),
+            link("Earth", Some("Moon"), "planet"),
+        ], accepts);
+
+        assert_eq!(errors, vec![
+            (1, LinkError::Invalid("A moon can not orbit Some(\"sun\")".to_string())),
+            (2, LinkError::Orphan),
+        ]);
+        assert_eq!(trees, vec![Tree::leaf("sun")]);
+    }
+
+    #[test]
+    fn uses_first_body_with_a_name() {
+        let (trees, errors) = resolve(vec![
+            link("Sun", None, "sun"),
+            link("Earth", Some("Sun"), "planet"),
+            link("Earth", Some("Sun"), "planet"),
+            link("Moon", Some("Earth"), "moon"),
+        ], accepts);
+
+        assert_eq!(errors, vec![]);
+        assert_eq!(trees[0].satellites[0].satellites, vec![Tree::leaf("moon")]);
+        assert_eq!(trees[0].satellites[1].satellites, vec![]);
+    }
+}
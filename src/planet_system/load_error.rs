@@ -0,0 +1,123 @@
+use std::{
+    error::Error,
+    fmt,
+};
+
+use crate::planet_system::{
+    planet_system::PlanetSystem,
+    units::{self, SourceUnits},
+};
+
+/// Error found while loading a file, pointing out where in the file it is and which value caused it.
+#[derive(Clone, PartialEq)]
+pub struct LoadError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub column: Option<String>,
+    pub value: Option<String>,
+    pub message: String,
+}
+
+impl LoadError {
+    pub fn new(message: impl Into<String>) -> LoadError {
+        LoadError {
+            file: String::new(),
+            line: None,
+            column: None,
+            value: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_value(column: &str, value: &str, message: impl Into<String>) -> LoadError {
+        LoadError::new(message).in_column(column, value)
+    }
+
+    pub fn in_file(mut self, file: &str) -> LoadError {
+        self.file = file.to_string();
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> LoadError {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn in_column(mut self, column: &str, value: &str) -> LoadError {
+        self.column = Some(column.to_string());
+        self.value = Some(value.to_string());
+        self
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file)?;
+
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+
+        if let (Some(column), Some(value)) = (&self.column, &self.value) {
+            write!(f, ": column '{}' = '{}'", column, value)?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl fmt::Debug for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Error for LoadError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Leave out rows (or planet systems in JSON files) that can not be loaded, instead of failing the whole file.
+    /// The errors for those rows are returned as warnings.
+    pub skip_bad_rows: bool,
+    /// Name of the planet system in files that do not name it, like solar CSV files. The name of the file without its
+    /// extension if not given.
+    pub system_name: Option<String>,
+    /// Header names for the columns of CSV files as (column, alias), added to the aliases of `CsvColumns`.
+    pub column_aliases: Vec<(String, String)>,
+}
+
+/// Planet systems loaded from a file, together with the errors for the rows that were skipped.
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    pub planet_systems: Vec<PlanetSystem>,
+    pub warnings: Vec<LoadError>,
+}
+
+impl LoadReport {
+    /// Returns `error` if bad rows should not be skipped, and keeps it as a warning otherwise.
+    pub fn skip_or_fail(&mut self, options: &LoadOptions, error: LoadError) -> Result<(), LoadError> {
+        if options.skip_bad_rows {
+            self.warnings.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Converts the values of every planet system from the units of the file into the units of the model.
+    pub fn normalise(mut self, units: &SourceUnits) -> LoadReport {
+        self.planet_systems.iter_mut().for_each(|ps| units.normalise(ps));
+        self
+    }
+
+    /// Same as `normalise` for the planet systems of a catalogue file, in the units of `SourceUnits::catalogue` with
+    /// the unit of the semi-major axes declared for each system, see `units::catalogue_semi_major_axis_unit`.
+    pub fn normalise_catalogue(mut self) -> LoadReport {
+        for planet_system in self.planet_systems.iter_mut() {
+            planet_system.semi_major_axis_unit = units::catalogue_semi_major_axis_unit(&planet_system.name);
+            SourceUnits::catalogue(planet_system.semi_major_axis_unit).normalise(planet_system);
+        }
+
+        self
+    }
+}
@@ -1,5 +1,19 @@
+pub mod celestial_body;
 pub mod center_star;
+pub mod consistency;
+pub mod csv_columns;
+pub mod epoch;
+pub mod file_format;
+pub mod habitable_zone;
+pub mod hierarchy;
+pub mod load_error;
 pub mod moon;
+pub mod orbit;
+pub mod physics;
 pub mod planet;
 pub mod planet_system;
-pub mod planet_system_csv;
\ No newline at end of file
+pub mod planet_system_csv;
+pub mod planet_system_json;
+pub mod provenance;
+pub mod units;
+pub mod validation;
\ No newline at end of file
@@ -1,22 +1,75 @@
-#[derive(Debug, Clone, Default)]
+use std::error::Error;
+use crate::{
+    planet_system::{
+        celestial_body::{CelestialBody, OrbitingBody},
+        consistency::Inferred,
+        provenance::Provenance,
+        units::{BodyUnits, Length, Mass, Time},
+    },
+    util::ui::FieldEditable,
+};
+
+#[derive(Debug, Clone, Default, FieldEditable)]
 pub struct Moon {
     pub name: String,
-    pub mass: f32,
-    pub radius: f32,
-    pub semi_major_axis: f32,
-    pub eccentricity: f32,
-    pub orbital_period: u32
+    /// Measurements are `None` if they are not known
+    pub mass: Option<f64>,
+    pub radius: Option<f64>,
+    pub semi_major_axis: Option<f64>,
+    pub eccentricity: Option<f64>,
+    pub orbital_period: Option<f64>,
+    pub picture_url: String,
+    /// Moons orbiting the moon
+    pub moons: Vec<Moon>,
+    pub provenance: Provenance,
+    pub inferred: Inferred,
+}
+
+impl CelestialBody for Moon {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &'static str {
+        "moon"
+    }
+
+    fn mass(&self) -> Option<Mass> {
+        self.mass.map(|m| Mass::new(m, BodyUnits::MODEL.mass))
+    }
+
+    fn radius(&self) -> Option<Length> {
+        self.radius.map(|r| Length::new(r, BodyUnits::MODEL.radius))
+    }
+
+    fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
 }
 
-impl From<Vec<String>> for Moon {
-    fn from(value: Vec<String>) -> Self {
-        Moon {
-            name: value[0].to_string(),
-            mass: value[1].parse::<f32>().unwrap_or_default(),
-            radius: value[2].parse::<f32>().unwrap_or_default(),
-            semi_major_axis: value[3].parse::<f32>().unwrap_or_default(),
-            eccentricity: value[4].parse::<f32>().unwrap_or_default(),
-            orbital_period: value[5].parse::<u32>().unwrap_or_default(),
-        }
+impl OrbitingBody for Moon {
+    fn semi_major_axis(&self) -> Option<Length> {
+        self.semi_major_axis.map(|a| Length::new(a, BodyUnits::MODEL.semi_major_axis))
+    }
+
+    fn eccentricity(&self) -> Option<f64> {
+        self.eccentricity
+    }
+
+    fn orbital_period(&self) -> Option<Time> {
+        self.orbital_period.map(|p| Time::new(p, BodyUnits::MODEL.orbital_period))
+    }
+
+    fn inferred(&self) -> &Inferred {
+        &self.inferred
+    }
+
+    fn inferred_mut(&mut self) -> &mut Inferred {
+        &mut self.inferred
+    }
+
+    fn set_orbit(&mut self, semi_major_axis: Length, orbital_period: Time) {
+        self.semi_major_axis = Some(semi_major_axis.get(BodyUnits::MODEL.semi_major_axis));
+        self.orbital_period = Some(orbital_period.get(BodyUnits::MODEL.orbital_period));
     }
 }
@@ -0,0 +1,242 @@
+use std::f64::consts::{PI, TAU};
+
+use crate::planet_system::{
+    consistency,
+    epoch::Epoch,
+    units::{Length, Mass, Time},
+};
+
+/// Elliptical orbit of a body around the body at one of its foci, in the plane of the orbit with the periapsis on the
+/// positive x axis. The files have no orientation of the orbits, so all orbits of a system are drawn in the same plane
+/// with the same periapsis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orbit {
+    pub semi_major_axis: Length,
+    pub eccentricity: f64,
+    pub orbital_period: Time,
+    /// Mean anomaly in radians at `epoch`, which the files have no place for. 0 at J2000 if not given, so every body
+    /// was at its periapsis then.
+    pub mean_anomaly_at_epoch: f64,
+    pub epoch: Epoch,
+}
+
+/// Position and velocity of a body on its orbit, relative to the body it orbits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct State {
+    /// In km
+    pub position: (f64, f64),
+    /// In km/s
+    pub velocity: (f64, f64),
+    /// Angle from the periapsis in radians, in [0, 2π)
+    pub true_anomaly: f64,
+}
+
+impl State {
+    /// Distance to the body it orbits, in km.
+    pub fn distance(&self) -> f64 {
+        self.position.0.hypot(self.position.1)
+    }
+
+    /// Speed in km/s.
+    pub fn speed(&self) -> f64 {
+        self.velocity.0.hypot(self.velocity.1)
+    }
+}
+
+impl Orbit {
+    /// `None` if the semi-major axis or orbital period is not positive, or the orbit is not an ellipse.
+    pub fn new(semi_major_axis: Length, eccentricity: f64, orbital_period: Time) -> Option<Orbit> {
+        (semi_major_axis.km() > 0.0 && orbital_period.days() > 0.0 && (0.0..1.0).contains(&eccentricity)).then_some(Orbit {
+            semi_major_axis,
+            eccentricity,
+            orbital_period,
+            mean_anomaly_at_epoch: 0.0,
+            epoch: Epoch::J2000,
+        })
+    }
+
+    /// Orbit of a body of `mass` around a body of `central_mass`. A semi-major axis or orbital period that is not known
+    /// is found from the other by Kepler's third law, and an eccentricity that is not known is taken as a circular
+    /// orbit.
+    pub fn of_body(
+        semi_major_axis: Option<Length>,
+        eccentricity: Option<f64>,
+        orbital_period: Option<Time>,
+        mass: Option<Mass>,
+        central_mass: Option<Mass>,
+    ) -> Option<Orbit> {
+        let eccentricity = eccentricity.unwrap_or_default();
+        let central_mass = central_mass.filter(|m| m.kg() > 0.0);
+
+        match (semi_major_axis, orbital_period, central_mass) {
+            (Some(a), Some(p), _) => Orbit::new(a, eccentricity, p),
+            (Some(a), None, Some(central_mass)) => Orbit::new(a, eccentricity, consistency::orbital_period(a, mass, central_mass)),
+            (None, Some(p), Some(central_mass)) => Orbit::new(consistency::semi_major_axis(p, mass, central_mass), eccentricity, p),
+            _ => None,
+        }
+    }
+
+    /// The same orbit with the body at `mean_anomaly`, in radians, at `epoch`.
+    pub fn with_mean_anomaly(self, mean_anomaly: f64, epoch: Epoch) -> Orbit {
+        Orbit {
+            mean_anomaly_at_epoch: mean_anomaly,
+            epoch,
+            ..self
+        }
+    }
+
+    /// Mean anomaly in radians, in [0, 2π), at `at`.
+    pub fn mean_anomaly(&self, at: Epoch) -> f64 {
+        (self.mean_anomaly_at_epoch + TAU * at.since(self.epoch).days() / self.orbital_period.days()).rem_euclid(TAU)
+    }
+
+    /// Position and velocity at `at`, found by solving Kepler's equation.
+    pub fn state(&self, at: Epoch) -> State {
+        let e = self.eccentricity;
+        let anomaly = eccentric_anomaly(self.mean_anomaly(at), e);
+        let (sin, cos) = anomaly.sin_cos();
+
+        // Rate of change of the eccentric anomaly in radians per second
+        let rate = TAU / self.orbital_period.seconds() / (1.0 - e * cos);
+        let a = self.semi_major_axis.km();
+        let b = a * (1.0 - e.powi(2)).sqrt();
+        let true_anomaly = 2.0 * ((1.0 + e).sqrt() * (anomaly / 2.0).sin()).atan2((1.0 - e).sqrt() * (anomaly / 2.0).cos());
+
+        State {
+            position: position(self.semi_major_axis, e, anomaly),
+            velocity: (-a * sin * rate, b * cos * rate),
+            true_anomaly: true_anomaly.rem_euclid(TAU),
+        }
+    }
+
+    /// `points` + 1 positions around the orbit, the last one the same as the first, for drawing it.
+    pub fn path(&self, points: usize) -> Vec<(f64, f64)> {
+        (0..=points)
+            .map(|i| position(self.semi_major_axis, self.eccentricity, TAU * i as f64 / points as f64))
+            .collect()
+    }
+}
+
+/// Solves Kepler's equation M = E - e sin E for the eccentric anomaly E, by Newton's method.
+/// `eccentricity` must be in [0, 1).
+pub fn eccentric_anomaly(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let mean_anomaly = mean_anomaly.rem_euclid(TAU);
+
+    // Starting at π converges for every mean anomaly when the orbit is very eccentric
+    let mut anomaly = if eccentricity < 0.8 { mean_anomaly } else { PI };
+
+    for _ in 0..50 {
+        let step = (anomaly - eccentricity * anomaly.sin() - mean_anomaly) / (1.0 - eccentricity * anomaly.cos());
+        anomaly -= step;
+
+        if step.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    anomaly
+}
+
+/// Position in km in the plane of the orbit at `eccentric_anomaly`, with the body it orbits at the origin and the
+/// periapsis on the positive x axis.
+pub fn position(semi_major_axis: Length, eccentricity: f64, eccentric_anomaly: f64) -> (f64, f64) {
+    let a = semi_major_axis.km();
+    let b = a * (1.0 - eccentricity.powi(2)).sqrt();
+
+    (a * (eccentric_anomaly.cos() - eccentricity), b * eccentric_anomaly.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::planet_system::units::{LengthUnit, TimeUnit};
+
+    fn orbit(eccentricity: f64) -> Orbit {
+        Orbit::new(Length::new(1.0e6, LengthUnit::Kilometre), eccentricity, Time::new(10.0, TimeUnit::Day)).unwrap()
+    }
+
+    fn days_after_j2000(days: f64) -> Epoch {
+        Epoch::J2000.after(Time::from_days(days))
+    }
+
+    #[test]
+    fn rejects_orbits_that_are_not_ellipses() {
+        let (a, period) = (Length::new(1.0e6, LengthUnit::Kilometre), Time::new(10.0, TimeUnit::Day));
+
+        assert_eq!(Orbit::new(a, 1.0, period), None);
+        assert_eq!(Orbit::new(a, -0.1, period), None);
+        assert_eq!(Orbit::new(Length::new(0.0, LengthUnit::Kilometre), 0.5, period), None);
+        assert_eq!(Orbit::new(a, 0.5, Time::new(0.0, TimeUnit::Day)), None);
+    }
+
+    #[test]
+    fn mean_anomaly_wraps_around_every_period() {
+        let orbit = orbit(0.0);
+
+        assert!((orbit.mean_anomaly(days_after_j2000(2.5)) - PI / 2.0).abs() < 1e-12);
+        assert!((orbit.mean_anomaly(days_after_j2000(12.5)) - PI / 2.0).abs() < 1e-12);
+        assert!((orbit.mean_anomaly(days_after_j2000(-7.5)) - PI / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mean_anomaly_starts_at_its_epoch() {
+        let orbit = orbit(0.0).with_mean_anomaly(PI, days_after_j2000(5.0));
+
+        assert!((orbit.mean_anomaly(days_after_j2000(5.0)) - PI).abs() < 1e-12);
+        assert!(orbit.mean_anomaly(days_after_j2000(10.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn state_at_periapsis_and_apoapsis() {
+        let orbit = orbit(0.5);
+
+        let periapsis = orbit.state(Epoch::J2000);
+        assert!((periapsis.distance() - 0.5e6).abs() < 1e-6);
+        assert_eq!(periapsis.true_anomaly, 0.0);
+
+        let apoapsis = orbit.state(days_after_j2000(5.0));
+        assert!((apoapsis.distance() - 1.5e6).abs() < 1e-6);
+        assert!((apoapsis.true_anomaly - PI).abs() < 1e-9);
+        assert!(apoapsis.speed() < periapsis.speed());
+    }
+
+    #[test]
+    fn circular_orbit_has_constant_speed() {
+        let orbit = orbit(0.0);
+        let speed = TAU * 1.0e6 / (10.0 * 86400.0);
+
+        for days in [0.0, 1.0, 2.5, 7.0] {
+            assert!((orbit.state(days_after_j2000(days)).speed() - speed).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn eccentric_anomaly_solves_keplers_equation() {
+        for eccentricity in [0.0, 0.1, 0.5, 0.9, 0.99] {
+            for i in 0..36 {
+                let mean_anomaly = TAU * i as f64 / 36.0;
+                let anomaly = eccentric_anomaly(mean_anomaly, eccentricity);
+
+                let error = (anomaly - eccentricity * anomaly.sin() - mean_anomaly + PI).rem_euclid(TAU) - PI;
+                assert!(error.abs() < 1e-9, "M = {}, e = {}: E = {}", mean_anomaly, eccentricity, anomaly);
+            }
+        }
+    }
+
+    #[test]
+    fn eccentric_anomaly_of_circular_orbit_is_mean_anomaly() {
+        assert_eq!(eccentric_anomaly(1.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn position_at_periapsis_and_apoapsis() {
+        let a = Length::new(1.0e6, LengthUnit::Kilometre);
+
+        let (x, y) = position(a, 0.5, 0.0);
+        assert!((x - 0.5e6).abs() < 1e-6 && y.abs() < 1e-6);
+
+        let (x, y) = position(a, 0.5, PI);
+        assert!((x + 1.5e6).abs() < 1e-6 && y.abs() < 1e-6);
+    }
+}
@@ -0,0 +1,87 @@
+use std::f64::consts::PI;
+
+use crate::{
+    planet_system::{
+        celestial_body::CelestialBody,
+        units::{Length, Mass},
+    },
+    util::number_format::format_rounded,
+};
+
+/// Gravitational constant in m^3 kg^-1 s^-2.
+pub const G: f64 = 6.674e-11;
+/// Stefan–Boltzmann constant in W m^-2 K^-4.
+pub const STEFAN_BOLTZMANN: f64 = 5.670374419e-8;
+/// Luminosity of the sun in W.
+pub const SOLAR_LUMINOSITY: f64 = 3.828e26;
+/// Share of the light of the sun reflected by the Earth, used for planets with an unknown albedo.
+pub const EARTH_BOND_ALBEDO: f64 = 0.306;
+
+/// Bulk density in kg/m^3, `None` if the mass or radius is not positive.
+pub fn density(mass: Mass, radius: Length) -> Option<f64> {
+    valid(mass, radius).then(|| mass.kg() / (4.0 / 3.0 * PI * radius.m().powi(3)))
+}
+
+/// Surface gravity in m/s^2, `None` if the mass or radius is not positive.
+pub fn surface_gravity(mass: Mass, radius: Length) -> Option<f64> {
+    valid(mass, radius).then(|| G * mass.kg() / radius.m().powi(2))
+}
+
+/// Escape velocity from the surface in m/s, `None` if the mass or radius is not positive.
+pub fn escape_velocity(mass: Mass, radius: Length) -> Option<f64> {
+    valid(mass, radius).then(|| (2.0 * G * mass.kg() / radius.m()).sqrt())
+}
+
+fn valid(mass: Mass, radius: Length) -> bool {
+    mass.kg() > 0.0 && radius.km() > 0.0
+}
+
+/// Luminosity in W of a black body with the radius and effective temperature of a star.
+pub fn luminosity(radius: Length, effective_temperature: f64) -> Option<f64> {
+    (radius.km() > 0.0 && effective_temperature > 0.0)
+        .then(|| 4.0 * PI * radius.m().powi(2) * STEFAN_BOLTZMANN * effective_temperature.powi(4))
+}
+
+/// Radius of a black body with `luminosity` in W and the effective temperature, the inverse of `luminosity`.
+pub fn radius_of_luminosity(luminosity: f64, effective_temperature: f64) -> Option<Length> {
+    (luminosity > 0.0 && effective_temperature > 0.0)
+        .then(|| Length::from_m((luminosity / (4.0 * PI * STEFAN_BOLTZMANN * effective_temperature.powi(4))).sqrt()))
+}
+
+/// Temperature in K of a planet with `albedo` that absorbs the light of its star evenly over its surface, at
+/// `semi_major_axis` from a star with the radius and effective temperature.
+pub fn equilibrium_temperature(radius: Length, effective_temperature: f64, semi_major_axis: Length, albedo: f64) -> Option<f64> {
+    (radius.km() > 0.0 && effective_temperature > 0.0 && semi_major_axis.km() > 0.0)
+        .then(|| effective_temperature * (radius.km() / (2.0 * semi_major_axis.km())).sqrt() * (1.0 - albedo).powf(0.25))
+}
+
+/// Harvard spectral class of a main sequence star with the effective temperature, from hot (O) to cool (M).
+pub fn spectral_class(effective_temperature: f64) -> Option<char> {
+    match effective_temperature {
+        t if t >= 30000.0 => Some('O'),
+        t if t >= 10000.0 => Some('B'),
+        t if t >= 7500.0 => Some('A'),
+        t if t >= 6000.0 => Some('F'),
+        t if t >= 5200.0 => Some('G'),
+        t if t >= 3700.0 => Some('K'),
+        t if t > 0.0 => Some('M'),
+        _ => None,
+    }
+}
+
+/// Rows of density, surface gravity and escape velocity, shown after the fields of a body.
+pub fn body_fields(body: &(impl CelestialBody + ?Sized)) -> Vec<(&'static str, String)> {
+    vec![
+        ("density", display(body.density().map(|d| d / 1000.0), "g/cm³")),
+        ("surface_gravity", display(body.surface_gravity(), "m/s²")),
+        ("escape_velocity", display(body.escape_velocity().map(|v| v / 1000.0), "km/s")),
+    ]
+}
+
+/// Value rounded to four significant digits, followed by `unit`, or "—" if it could not be computed.
+pub fn display(value: Option<f64>, unit: &str) -> String {
+    match value {
+        Some(value) => format!("{} {}", format_rounded(value, 4), unit),
+        None => "—".to_string(),
+    }
+}
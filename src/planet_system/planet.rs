@@ -5,31 +5,118 @@ use std::{
 };
 
 use crate::{
-    planet_system::moon::Moon,
+    planet_system::{
+        celestial_body::{CelestialBody, OrbitingBody},
+        center_star::CenterStar,
+        consistency::Inferred,
+        habitable_zone::Zone,
+        moon::Moon,
+        physics,
+        provenance::Provenance,
+        units::{BodyUnits, Length, Mass, Time},
+    },
     util::ui::FieldEditable
 };
 
 #[derive(Debug, Clone, Default, FieldEditable)]
 pub struct Planet {
     pub name: String,
-    pub mass: f32,
-    pub radius: f32,
-    pub semi_major_axis: f32,
-    pub eccentricity: f32,
-    pub orbital_period: f32,
-    pub moons: Vec<Moon>
+    /// Measurements are `None` if they are not known
+    pub mass: Option<f64>,
+    pub radius: Option<f64>,
+    pub semi_major_axis: Option<f64>,
+    pub eccentricity: Option<f64>,
+    pub orbital_period: Option<f64>,
+    /// Name of the star the planet orbits, or `center_star::BARYCENTRE` if it orbits every star of its system. Planets
+    /// that name no star orbit the first star, see `PlanetSystem::host`.
+    pub orbits: String,
+    pub picture_url: String,
+    pub moons: Vec<Moon>,
+    pub provenance: Provenance,
+    pub inferred: Inferred,
 }
 
-impl From<Vec<String>> for Planet {
-    fn from(value: Vec<String>) -> Self {
-        Planet {
-            name: value[0].to_string(),
-            mass: value[1].parse::<f32>().unwrap_or_default(),
-            radius: value[2].parse::<f32>().unwrap_or_default(),
-            semi_major_axis: value[3].parse::<f32>().unwrap_or_default(),
-            eccentricity: value[4].parse::<f32>().unwrap_or_default(),
-            orbital_period: value[5].parse::<f32>().unwrap_or_default(),
-            moons: vec![],
+impl CelestialBody for Planet {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &'static str {
+        "planet"
+    }
+
+    fn mass(&self) -> Option<Mass> {
+        self.mass.map(|m| Mass::new(m, BodyUnits::MODEL.mass))
+    }
+
+    fn radius(&self) -> Option<Length> {
+        self.radius.map(|r| Length::new(r, BodyUnits::MODEL.radius))
+    }
+
+    fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
+}
+
+impl OrbitingBody for Planet {
+    fn semi_major_axis(&self) -> Option<Length> {
+        self.semi_major_axis.map(|a| Length::new(a, BodyUnits::MODEL.semi_major_axis))
+    }
+
+    fn eccentricity(&self) -> Option<f64> {
+        self.eccentricity
+    }
+
+    fn orbital_period(&self) -> Option<Time> {
+        self.orbital_period.map(|p| Time::new(p, BodyUnits::MODEL.orbital_period))
+    }
+
+    fn inferred(&self) -> &Inferred {
+        &self.inferred
+    }
+
+    fn inferred_mut(&mut self) -> &mut Inferred {
+        &mut self.inferred
+    }
+
+    fn set_orbit(&mut self, semi_major_axis: Length, orbital_period: Time) {
+        self.semi_major_axis = Some(semi_major_axis.get(BodyUnits::MODEL.semi_major_axis));
+        self.orbital_period = Some(orbital_period.get(BodyUnits::MODEL.orbital_period));
+    }
+}
+
+impl Planet {
+    /// Equilibrium temperature in K around `star`, with the albedo of the Earth, see `physics::equilibrium_temperature`.
+    pub fn equilibrium_temperature(&self, star: &CenterStar) -> Option<f64> {
+        physics::equilibrium_temperature(star.radius()?, star.effective_temperature?, self.semi_major_axis()?, physics::EARTH_BOND_ALBEDO)
+    }
+
+    /// Part of the habitable zone of `star` the planet orbits in, `None` if it is outside of it or either is unknown.
+    pub fn habitable_zone(&self, star: &CenterStar) -> Option<Zone> {
+        match self.semi_major_axis() {
+            Some(a) if a.km() > 0.0 => star.habitable_zone()?.zone(a),
+            _ => None,
+        }
+    }
+
+    /// Every moon of the planet and of its moons, depth first, with the body it orbits.
+    pub fn all_moons(&self) -> Vec<(&dyn CelestialBody, &Moon)> {
+        let mut moons: Vec<(&dyn CelestialBody, &Moon)> = vec![];
+        let mut stack: Vec<(&dyn CelestialBody, &Moon)> = self.moons.iter().rev().map(|m| (self as &dyn CelestialBody, m)).collect();
+
+        while let Some((central, moon)) = stack.pop() {
+            moons.push((central, moon));
+            stack.extend(moon.moons.iter().rev().map(|m| (moon as &dyn CelestialBody, m)));
         }
+
+        moons
+    }
+
+    /// Equilibrium temperature and habitable zone around `star`, formatted to be shown after the derived properties.
+    pub fn orbit_fields(&self, star: &CenterStar) -> Vec<(&'static str, String)> {
+        vec![
+            ("equilibrium_temperature", physics::display(self.equilibrium_temperature(star), "K")),
+            ("habitable_zone", self.habitable_zone(star).map(|z| z.name()).unwrap_or("—").to_string()),
+        ]
     }
 }
@@ -1,18 +1,34 @@
 use std::{
+    borrow::Cow,
     collections::HashMap,
-    mem,
+    fs,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
 };
 use std::error::Error;
 
+use serde::Serialize;
+
 use crate::{
     planet_system::{
-        center_star::CenterStar,
+        celestial_body::CelestialBody,
+        center_star::{CenterStar, BARYCENTRE},
+        csv_columns::{parse_cell, parse_measurement, parse_optional_cell, CsvColumns, SOLAR_COLUMNS},
+        file_format::FileFormat,
+        hierarchy::{self, Link, Member, Tree},
+        load_error::{LoadError, LoadOptions, LoadReport},
+        provenance::Provenance,
         planet::Planet,
+        units::{LengthUnit, SourceUnits},
         moon::Moon,
-        planet_system_csv::PlanetSystemsCSV
+        planet_system_csv::{self, PlanetSystemsCSV},
+        planet_system_json::PlanetSystemJSON,
     },
     util::{
-        file_reader::read_lines,
+        csv::write_record,
+        json_formatter::CatalogueFormatter,
+        number_format::format_optional,
         ui::FieldEditable,
     },
 };
@@ -23,75 +39,604 @@ pub enum Types {
     Moon(Moon, String),
 }
 
-impl From<Vec<String>> for Types {
-    fn from(value: Vec<String>) -> Self {
+/// Cells of the measurements of planets and moons in `csv_columns::SOLAR_COLUMNS`.
+const ORBITING_CELLS: [(&str, usize); 5] = [
+    ("mass", 1), ("radius", 2), ("semi_major_axis", 3), ("eccentricity", 4), ("orbital_period", 5),
+];
+
+/// Parses a row in the layout of `csv_columns::SOLAR_COLUMNS`.
+impl TryFrom<Vec<String>> for Types {
+    type Error = LoadError;
+
+    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
+        // Empty cells are allowed, as not every type of body uses every column
+        for i in [1, 2, 3, 4, 5, 8] {
+            if !value[i].trim().is_empty() {
+                parse_cell::<f64>(&value, &SOLAR_COLUMNS, i)?;
+            }
+        }
+
         let c = value[6].clone().to_string();
+        let measurement = |i: usize| parse_measurement(&value, &SOLAR_COLUMNS, i);
 
         match value[7].as_str() {
-            "sun" => Types::CenterStar(CenterStar::from(value)),
-            "planet" => Types::Planet(Planet::from(value), c),
-            "moon" => {
-                Types::Moon(Moon::from(value), c)
-            },
-            v => panic!("Unknown type: {}", v)
+            "sun" => Ok(Types::CenterStar(CenterStar {
+                name: value[0].to_string(),
+                mass: measurement(1)?,
+                radius: measurement(2)?,
+                effective_temperature: measurement(8)?,
+                provenance: Provenance::with_cells(&value, &[("mass", 1), ("radius", 2), ("effective_temperature", 8)]),
+                ..CenterStar::default()
+            })),
+            "planet" => Ok(Types::Planet(Planet {
+                name: value[0].to_string(),
+                mass: measurement(1)?,
+                radius: measurement(2)?,
+                semi_major_axis: measurement(3)?,
+                eccentricity: parse_optional_cell(&value, &SOLAR_COLUMNS, 4)?,
+                orbital_period: measurement(5)?,
+                provenance: Provenance::with_cells(&value, &ORBITING_CELLS),
+                ..Planet::default()
+            }, c)),
+            "moon" => Ok(Types::Moon(Moon {
+                name: value[0].to_string(),
+                mass: measurement(1)?,
+                radius: measurement(2)?,
+                semi_major_axis: measurement(3)?,
+                eccentricity: parse_optional_cell(&value, &SOLAR_COLUMNS, 4)?,
+                orbital_period: measurement(5)?,
+                provenance: Provenance::with_cells(&value, &ORBITING_CELLS),
+                ..Moon::default()
+            }, c)),
+            v => Err(LoadError::invalid_value(SOLAR_COLUMNS[7], v, "Unknown type, expected 'sun', 'planet' or 'moon'"))
         }
     }
 }
 
+impl Types {
+    /// Sets the file and line the body was loaded from, see `Provenance::locate`.
+    pub fn located(mut self, file: &str, line: usize) -> Types {
+        match &mut self {
+            Types::CenterStar(star) => star.provenance.locate(file, Some(line)),
+            Types::Planet(planet, _) => planet.provenance.locate(file, Some(line)),
+            Types::Moon(moon, _) => moon.provenance.locate(file, Some(line)),
+        }
+
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Types::CenterStar(star) => &star.name,
+            Types::Planet(planet, _) => &planet.name,
+            Types::Moon(moon, _) => &moon.name,
+        }
+    }
+
+    /// Formats the body as a row in the layout of `csv_columns::SOLAR_COLUMNS`, the center star orbits "None".
+    pub fn to_line(&self) -> String {
+        let (name, provenance, values, central_body, body_type, effective_temperature) = match self {
+            Types::CenterStar(star) => (
+                &star.name, &star.provenance, [star.mass, star.radius, Some(0.0), Some(0.0), Some(0.0)], "None", "sun",
+                star.provenance.format("effective_temperature", star.effective_temperature)
+            ),
+            Types::Planet(planet, c) => (
+                &planet.name, &planet.provenance,
+                [planet.mass, planet.radius, planet.semi_major_axis, planet.eccentricity, planet.orbital_period],
+                c.as_str(), "planet", String::new()
+            ),
+            Types::Moon(moon, c) => (
+                &moon.name, &moon.provenance, [moon.mass, moon.radius, moon.semi_major_axis, moon.eccentricity, moon.orbital_period],
+                c.as_str(), "moon", String::new()
+            ),
+        };
+
+        let cells = [name.clone()].into_iter()
+            .chain(ORBITING_CELLS.iter().zip(values).map(|((field, _), value)| provenance.format(field, value)))
+            .chain([central_body.to_string(), body_type.to_string(), effective_temperature])
+            .collect::<Vec<String>>();
+
+        write_record(&cells)
+    }
+}
+
 #[derive(Debug, Clone, Default, FieldEditable)]
 pub struct PlanetSystem {
     pub name: String,
-    pub center_star: CenterStar,
-    pub planets: Vec<Planet>
+    pub picture_url: String,
+    /// The first star is the primary star of the system
+    pub stars: Vec<CenterStar>,
+    pub planets: Vec<Planet>,
+    /// Unit of the semi-major axes of the planets in catalogue files, which the system is saved in
+    pub semi_major_axis_unit: LengthUnit,
 }
 
 impl PlanetSystem {
+    /// The stars, followed by every planet and its moons.
+    pub fn bodies(&self) -> Vec<&dyn CelestialBody> {
+        let mut bodies: Vec<&dyn CelestialBody> = self.stars.iter().map(|s| s as &dyn CelestialBody).collect();
+
+        for planet in &self.planets {
+            bodies.push(planet);
+            bodies.extend(planet.all_moons().into_iter().map(|(_, m)| m as &dyn CelestialBody));
+        }
+
+        bodies
+    }
+
+    /// The stars with the planets orbiting them, followed by the barycentre of the stars if planets orbit it. Planets
+    /// orbiting a star that is not in the system come last, without a star.
+    pub fn hierarchy(&self) -> Vec<Tree<Member<'_>>> {
+        let mut trees: Vec<Tree<Member>> = self.stars.iter().map(|s| Tree::leaf(Member::Star(s))).collect();
+        let mut barycentre = Tree::leaf(Member::Barycentre);
+        let mut unknown: Vec<Tree<Member>> = vec![];
+
+        for planet in &self.planets {
+            let tree = Tree { body: Member::Planet(planet), satellites: hierarchy::moons(&planet.moons) };
+
+            // The same stars as `host` finds
+            match planet.orbits.as_str() {
+                name if name.eq_ignore_ascii_case(BARYCENTRE) => barycentre.satellites.push(tree),
+                "" if !self.stars.is_empty() => trees[0].satellites.push(tree),
+                name => match self.stars.iter().position(|s| s.name == name) {
+                    Some(i) => trees[i].satellites.push(tree),
+                    None => unknown.push(tree),
+                },
+            }
+        }
+
+        if !barycentre.satellites.is_empty() {
+            trees.push(barycentre);
+        }
+
+        trees.extend(unknown);
+
+        trees
+    }
+
     pub fn new() -> PlanetSystem {
         PlanetSystem {
             name: "".to_string(),
-            center_star: CenterStar::new(),
+            picture_url: "".to_string(),
+            stars: vec![CenterStar::new()],
             planets: vec![],
+            semi_major_axis_unit: LengthUnit::Kilometre,
         }
     }
 
-    pub fn new_systems_from_file(file_name: String) -> Vec<PlanetSystem> {
+    pub fn star(&self, name: &str) -> Option<&CenterStar> {
+        self.stars.iter().find(|s| s.name == name)
+    }
+
+    /// The star `planet` orbits, or a star standing in for the barycentre of every star of the system if it orbits
+    /// all of them, see `CenterStar::barycentre`. Planets that name no star orbit the primary star. `None` if the
+    /// planet orbits a star that is not in the system.
+    pub fn host(&self, planet: &Planet) -> Option<Cow<'_, CenterStar>> {
+        match planet.orbits.as_str() {
+            "" => self.stars.first().map(Cow::Borrowed),
+            name if name.eq_ignore_ascii_case(BARYCENTRE) => Some(Cow::Owned(CenterStar::barycentre(&self.stars))),
+            name => self.star(name).map(Cow::Borrowed),
+        }
+    }
+
+    /// Points the planets orbiting the star named `old` at `new`, for when the star is renamed.
+    pub fn rename_host(&mut self, old: &str, new: &str) {
+        self.planets.iter_mut()
+            .filter(|p| p.orbits == old)
+            .for_each(|p| p.orbits = new.to_string());
+    }
+
+    /// Sets the file and line every star, planet and moon was loaded from, see `Provenance::locate`.
+    pub fn locate(&mut self, file: &str, line: Option<usize>) {
+        self.stars.iter_mut().for_each(|s| s.provenance.locate(file, line));
+
+        for planet in self.planets.iter_mut() {
+            planet.provenance.locate(file, line);
+
+            let mut moons: Vec<&mut Moon> = planet.moons.iter_mut().collect();
+
+            while let Some(moon) = moons.pop() {
+                moon.provenance.locate(file, line);
+                moons.extend(moon.moons.iter_mut());
+            }
+        }
+    }
+
+    /// Picks the loader from the format of the file, see `FileFormat::from_file`.
+    pub fn load_systems_from_file(file_name: &str, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+        PlanetSystem::load_systems(file_name, FileFormat::from_file(file_name, options), options)
+    }
+
+    pub fn load_systems(file_name: &str, format: FileFormat, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+        match format {
+            FileFormat::Csv => PlanetSystem::new_systems_from_file(
+                file_name,
+                &CsvColumns::planet_systems().with_configured_aliases(&options.column_aliases),
+                options,
+            ),
+            FileFormat::SolarCsv => PlanetSystem::new_systems_from_solar_file(
+                file_name,
+                &CsvColumns::solar().with_configured_aliases(&options.column_aliases),
+                options,
+            ),
+            FileFormat::Json => PlanetSystem::new_systems_from_json_file(file_name, options),
+        }
+    }
+
+    pub fn new_systems_from_file(file_name: &str, columns: &CsvColumns, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+        let mut report = LoadReport::default();
+
         let mut planets: HashMap<String, Vec<Planet>> = HashMap::new();
-        let mut stars: HashMap<String, CenterStar> = HashMap::new();
+        let mut stars: HashMap<String, Vec<CenterStar>> = HashMap::new();
+        let mut picture_urls: HashMap<String, String> = HashMap::new();
+        // Planet systems are kept in the order they first appear in, so converted files keep the order of the original
+        let mut system_names: Vec<String> = vec![];
 
-        read_lines(file_name).enumerate()
-            .filter(|(i, _)| i.clone() != 0)
-            .filter(|(_, line)| !line.as_ref().unwrap().is_empty())
-            .for_each(|(_, line)| {
-                let line = line.unwrap();
-                let planet_system_line = PlanetSystemsCSV::try_from(line).unwrap();
+        for (line, row) in columns.read_file(file_name)? {
+            let mut planet_system_line = match row.and_then(PlanetSystemsCSV::try_from) {
+                Ok(planet_system_line) => planet_system_line,
+                Err(e) => {
+                    report.skip_or_fail(options, e.in_file(file_name).at_line(line))?;
+                    continue;
+                }
+            };
 
-                stars.entry(planet_system_line.name.clone())
-                    .or_insert(planet_system_line.center_star);
+            planet_system_line.center_star.provenance.locate(file_name, Some(line));
 
-                let mut planet = planet_system_line.planet.to_owned();
-                let new_planet = planet_system_line.planet.to_owned();
+            if !stars.contains_key(&planet_system_line.name) {
+                system_names.push(planet_system_line.name.clone());
+            }
 
-                planets.entry(planet_system_line.name.clone())
-                    .and_modify(|list| list.push(mem::replace(&mut planet, new_planet)))
-                    .or_insert(vec![planet]);
-            });
+            // Every star named in the rows of a system is one of its stars, in the order they first appear in
+            let system_stars = stars.entry(planet_system_line.name.clone()).or_default();
 
-        let mut planet_systems: Vec<PlanetSystem> = vec![];
+            if !system_stars.iter().any(|s| s.name == planet_system_line.center_star.name) {
+                system_stars.push(planet_system_line.center_star);
+            }
 
-        planets.iter()
-            .for_each(|(system_name, planets)| {
-                let mut planet_list: Vec<Planet> = vec![];
+            picture_urls.entry(planet_system_line.name.clone())
+                .or_insert(planet_system_line.picture_url);
 
-                planets.iter().for_each(|p| planet_list.push(p.clone()));
+            if let Some(mut planet) = planet_system_line.planet {
+                planet.provenance.locate(file_name, Some(line));
 
-                planet_systems.push(PlanetSystem {
-                    name: system_name.clone(),
-                    center_star: stars.get(system_name).unwrap().clone(),
-                    planets: planet_list,
-                })
+                planets.entry(planet_system_line.name)
+                    .or_default()
+                    .push(planet);
+            }
+        }
 
+        for system_name in system_names {
+            report.planet_systems.push(PlanetSystem {
+                picture_url: picture_urls.remove(&system_name).unwrap_or_default(),
+                stars: stars.remove(&system_name).unwrap_or_default(),
+                planets: planets.remove(&system_name).unwrap_or_default(),
+                name: system_name,
+                semi_major_axis_unit: LengthUnit::Kilometre,
             });
+        }
+
+        Ok(report.normalise_catalogue())
+    }
+
+    /// Reads a file with one sun, planet or moon per line (files/csv/solarSystem.csv) into a single planet system.
+    /// Every body is placed under the body named in its `centralCelestialBody` column, see `hierarchy::resolve`:
+    /// planets orbit one of the suns, or all of them if they orbit `center_star::BARYCENTRE`, and moons orbit planets
+    /// or other moons. Bodies that can not be placed are skipped with the bodies orbiting them.
+    pub fn new_systems_from_solar_file(file_name: &str, columns: &CsvColumns, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+        let mut report = LoadReport::default();
+
+        let mut links: Vec<Link<Types>> = vec![];
+        // Line and `centralCelestialBody` of every link, for the errors of the bodies that can not be placed
+        let mut cells: Vec<(usize, String)> = vec![];
+
+        for (line, row) in columns.read_file(file_name)? {
+            match row.and_then(Types::try_from).map(|t| t.located(file_name, line)) {
+                Ok(body) => {
+                    let central = match &body {
+                        Types::CenterStar(_) => None,
+                        Types::Planet(_, c) if c.eq_ignore_ascii_case(BARYCENTRE) => None,
+                        Types::Planet(_, c) | Types::Moon(_, c) => Some(c.clone()),
+                    };
+
+                    cells.push((line, central.clone().unwrap_or_default()));
+                    links.push(Link { name: body.name().to_string(), central, body });
+                }
+                Err(e) => report.skip_or_fail(options, e.in_file(file_name).at_line(line))?,
+            }
+        }
+
+        let (trees, errors) = hierarchy::resolve(links, |central, body| match (central, body) {
+            (None, _) | (Some(Types::CenterStar(_)), Types::Planet(..)) | (Some(Types::Planet(..) | Types::Moon(..)), Types::Moon(..)) => Ok(()),
+            (_, Types::Moon(..)) => Err("Moons must orbit a planet or another moon".to_string()),
+            _ => Err("Planets must orbit a sun or the barycentre".to_string()),
+        });
+
+        for (i, error) in errors {
+            let (line, central) = &cells[i];
+
+            report.skip_or_fail(options, LoadError::invalid_value(SOLAR_COLUMNS[6], central, error.to_string())
+                .in_file(file_name)
+                .at_line(*line)
+            )?;
+        }
+
+        let mut stars: Vec<CenterStar> = vec![];
+        let mut planets: Vec<Planet> = vec![];
+
+        for tree in trees {
+            match tree.body {
+                Types::CenterStar(star) => {
+                    planets.extend(tree.satellites.into_iter().filter_map(|t| planet_of(t, &star.name)));
+                    stars.push(star);
+                }
+                body => planets.extend(planet_of(Tree { body, satellites: tree.satellites }, BARYCENTRE)),
+            }
+        }
+
+        // Planets are grouped by the star they orbit, so they are put back in the order of the file
+        planets.sort_by_key(|p| p.provenance.line);
+
+        if stars.is_empty() {
+            return Err(LoadError::new("No sun in file").in_file(file_name));
+        }
+
+        report.planet_systems.push(PlanetSystem {
+            name: options.system_name.clone().unwrap_or_else(|| file_stem(file_name)),
+            picture_url: String::new(),
+            stars,
+            planets,
+            semi_major_axis_unit: LengthUnit::Kilometre,
+        });
+
+        Ok(report.normalise(&SourceUnits::SOLAR_SYSTEM))
+    }
+
+    pub fn save_systems(planet_systems: &[PlanetSystem], file_name: String, format: FileFormat) -> Result<(), Box<dyn Error>> {
+        match format {
+            FileFormat::Csv => PlanetSystem::save_systems_to_file(planet_systems, file_name),
+            FileFormat::SolarCsv => PlanetSystem::save_systems_to_solar_file(planet_systems, file_name),
+            FileFormat::Json => PlanetSystem::save_systems_to_json_file(planet_systems, file_name),
+        }
+    }
+
+    pub fn save_systems_to_file(planet_systems: &[PlanetSystem], file_name: String) -> Result<(), Box<dyn Error>> {
+        let mut writer = BufWriter::new(File::create(&*file_name)?);
+
+        let planet_systems = SourceUnits::denormalised_catalogue(planet_systems);
+        let several_stars = planet_systems.iter().any(PlanetSystemsCSV::has_several_stars);
+
+        writeln!(writer, "{}", PlanetSystemsCSV::header(several_stars))?;
+
+        for line in planet_systems.iter().flat_map(PlanetSystemsCSV::from_system) {
+            writeln!(writer, "{}", line.to_line(several_stars))?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Writes a single planet system with one sun, planet or moon per line, the layout of files/csv/solarSystem.csv.
+    /// Moons are written after the planets, and after the moons they orbit.
+    /// The layout has no columns for picture URLs, so these are not saved.
+    pub fn save_systems_to_solar_file(planet_systems: &[PlanetSystem], file_name: String) -> Result<(), Box<dyn Error>> {
+        let planet_system = match SourceUnits::SOLAR_SYSTEM.denormalised(planet_systems).as_slice() {
+            [planet_system] => planet_system.clone(),
+            _ => return Err(LoadError::new(format!(
+                "Solar CSV files hold a single planet system, not {}", planet_systems.len()
+            )).in_file(&file_name).into()),
+        };
+
+        let mut writer = BufWriter::new(File::create(&*file_name)?);
+
+        writeln!(writer, "{}", write_record(&SOLAR_COLUMNS.map(String::from)))?;
+
+        for star in &planet_system.stars {
+            writeln!(writer, "{}", Types::CenterStar(star.clone()).to_line())?;
+        }
+
+        for planet in &planet_system.planets {
+            let central_body = match planet.orbits.as_str() {
+                "" => planet_system.stars.first().map(|s| s.name.clone()).unwrap_or_default(),
+                orbits => orbits.to_string(),
+            };
+
+            writeln!(writer, "{}", Types::Planet(planet.clone(), central_body).to_line())?;
+        }
+
+        for planet in &planet_system.planets {
+            for (central, moon) in planet.all_moons() {
+                writeln!(writer, "{}", Types::Moon(moon.clone(), central.name().to_string()).to_line())?;
+            }
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Bad planet systems can only be skipped if the file is valid JSON, and errors for skipped systems have no line numbers.
+    pub fn new_systems_from_json_file(file_name: &str, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+        let mut report = LoadReport::default();
+
+        let text = fs::read_to_string(file_name)
+            .map_err(|e| LoadError::new(e.to_string()).in_file(file_name))?;
+
+        let systems: Vec<PlanetSystemJSON> = match serde_json::from_str(&text) {
+            Ok(systems) => systems,
+            Err(e) if e.is_data() && options.skip_bad_rows => {
+                let values: Vec<serde_json::Value> = serde_json::from_str(&text)
+                    .map_err(|e| json_error(file_name, e))?;
+
+                let mut systems: Vec<PlanetSystemJSON> = vec![];
+
+                for (i, value) in values.into_iter().enumerate() {
+                    match serde_json::from_value(value) {
+                        Ok(system) => systems.push(system),
+                        Err(e) => report.warnings.push(LoadError::new(format!("Planet system {}: {}", i + 1, e)).in_file(file_name)),
+                    }
+                }
+
+                systems
+            }
+            Err(e) => return Err(json_error(file_name, e)),
+        };
+
+        for system in systems {
+            match PlanetSystem::try_from(system) {
+                Ok(mut planet_system) => {
+                    planet_system.locate(file_name, None);
+                    report.planet_systems.push(planet_system)
+                }
+                Err(e) => report.skip_or_fail(options, LoadError::new(e).in_file(file_name))?,
+            }
+        }
+
+        Ok(report.normalise_catalogue())
+    }
+
+    pub fn save_systems_to_json_file(planet_systems: &[PlanetSystem], file_name: String) -> Result<(), Box<dyn Error>> {
+        let systems: Vec<PlanetSystemJSON> = SourceUnits::denormalised_catalogue(planet_systems).iter()
+            .map(PlanetSystemJSON::from)
+            .collect();
+
+        let mut writer = BufWriter::new(File::create(&*file_name)?);
+        let mut serializer = serde_json::Serializer::with_formatter(&mut writer, CatalogueFormatter::default());
+
+        systems.serialize(&mut serializer)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Planet of a tree placed by `hierarchy::resolve`, orbiting `orbits`, with the moons orbiting it.
+fn planet_of(tree: Tree<Types>, orbits: &str) -> Option<Planet> {
+    match tree.body {
+        Types::Planet(mut planet, _) => {
+            planet.orbits = orbits.to_string();
+            planet.moons = moons_of(tree.satellites);
+            Some(planet)
+        }
+        _ => None,
+    }
+}
+
+fn moons_of(trees: Vec<Tree<Types>>) -> Vec<Moon> {
+    trees.into_iter()
+        .filter_map(|tree| match tree.body {
+            Types::Moon(mut moon, _) => {
+                moon.moons = moons_of(tree.satellites);
+                Some(moon)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn json_error(file_name: &str, error: serde_json::Error) -> LoadError {
+    let message = error.to_string();
+
+    // Drop the " at line x column y" serde_json adds, as the line is shown by the load error
+    let message = match message.rfind(" at line ") {
+        Some(i) => message[..i].to_string(),
+        None => message,
+    };
+
+    LoadError::new(message).in_file(file_name).at_line(error.line())
+}
+
+/// Name of a file without its directory and extension.
+fn file_stem(file_name: &str) -> String {
+    Path::new(file_name).file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lines of `file_name` and of its planet systems saved with `save`
+    fn round_trip<E>(file_name: &str, save: fn(&[PlanetSystem], String) -> Result<(), E>) -> (Vec<String>, Vec<String>)
+    where
+        E: std::fmt::Debug,
+    {
+        let planet_systems = PlanetSystem::load_systems_from_file(file_name, &LoadOptions::default()).unwrap().planet_systems;
+        let saved = std::env::temp_dir().join(format!("round_trip_{}_{}", std::process::id(), file_name.replace('/', "_")));
+
+        save(&planet_systems, saved.to_string_lossy().to_string()).unwrap();
+
+        let lines = |file: &Path| fs::read_to_string(file).unwrap().lines().map(str::to_string).collect::<Vec<String>>();
+        let (original, saved_lines) = (lines(Path::new(file_name)), lines(&saved));
+        fs::remove_file(saved).unwrap();
+
+        (original, saved_lines)
+    }
+
+    #[test]
+    fn catalogue_without_header_is_saved_as_it_was_read() {
+        let (original, saved) = round_trip("files/csv/planets_100.csv", PlanetSystem::save_systems_to_file);
+
+        assert_eq!(saved[0], planet_system_csv::HEADER);
+        assert_eq!(saved[1..], original[..]);
+    }
+
+    #[test]
+    fn catalogue_numbers_are_not_reformatted() {
+        let (original, saved) = round_trip("files/csv/planets.csv", PlanetSystem::save_systems_to_file);
+
+        assert_eq!(saved, original);
+    }
+
+    #[test]
+    fn solar_system_is_saved_as_it_was_read() {
+        let (original, saved) = round_trip("files/csv/solarSystem.csv", PlanetSystem::save_systems_to_solar_file);
+
+        // Blank lines are not kept
+        assert_eq!(saved, original.into_iter().filter(|line| !line.is_empty()).collect::<Vec<String>>());
+    }
+
+    #[test]
+    fn json_catalogue_is_saved_as_it_was_read() {
+        let original = fs::read_to_string("files/json/planets_100.json").unwrap();
+        let (_, saved) = round_trip("files/json/planets_100.json", PlanetSystem::save_systems_to_json_file);
+
+        assert_eq!(saved.join("\n"), original);
+    }
+
+    #[test]
+    fn changed_values_are_written_in_the_format_of_the_writer() {
+        let mut planet_systems = PlanetSystem::load_systems_from_file("files/csv/planets.csv", &LoadOptions::default())
+            .unwrap()
+            .planet_systems;
+        let planet = &mut planet_systems[0].planets[0];
+        planet.eccentricity = Some(0.5);
+        planet.mass = None;
+
+        let line = PlanetSystemsCSV::from_system(&planet_systems[0])[0].to_line(false);
+        let cells: Vec<&str> = line.split(',').collect();
+
+        assert_eq!(cells[8], "");
+        assert_eq!(cells[11], "0.5");
+    }
+
+    #[test]
+    fn unknown_measurements_are_written_as_null_in_json() {
+        let mut planet_systems = PlanetSystem::load_systems_from_file("files/json/planets_100.json", &LoadOptions::default())
+            .unwrap()
+            .planet_systems;
+        planet_systems[0].planets[0].mass = None;
+        let saved = std::env::temp_dir().join(format!("unknown_measurements_{}.json", std::process::id()));
+        let file_name = saved.to_string_lossy().to_string();
+
+        PlanetSystem::save_systems_to_json_file(&planet_systems[..1], file_name.clone()).unwrap();
+        let text = fs::read_to_string(&saved).unwrap();
+        let loaded = PlanetSystem::load_systems_from_file(&file_name, &LoadOptions::default()).unwrap().planet_systems;
+        fs::remove_file(saved).unwrap();
 
-        planet_systems
+        assert!(text.contains("\"mass\" : null"));
+        assert_eq!(loaded[0].planets[0].mass, None);
     }
 }
@@ -1,49 +1,157 @@
 use crate::{
     planet_system::{
-        center_star::CenterStar,
+        center_star::{CenterStar, BARYCENTRE},
+        consistency::Inferred,
+        csv_columns::{parse_measurement, parse_optional_cell, PLANET_SYSTEM_COLUMNS},
+        load_error::LoadError,
         planet::Planet,
-    }
+        planet_system::PlanetSystem,
+        provenance::Provenance,
+    },
+    util::csv::write_record,
 };
 
+pub const HEADER: &str = "#_psys_name,psys_pictureurl,st_name,st_mass,st_radius,st_efftemp,st_pictureurl,pl_name,pl_mass,pl_radius,pl_semimajor,pl_ecc,pl_orbper,pl_pictureurl";
+
+/// Column added after `HEADER` in files with planet systems that need it, see `PlanetSystemsCSV::has_several_stars`.
+pub const CIRCUMBINARY_COLUMN: &str = "pl_cbflag";
+
 pub struct PlanetSystemsCSV {
     pub name: String,
+    pub picture_url: String,
     pub center_star: CenterStar,
-    pub planet: Planet
+    /// `None` for rows that only add a star to the system
+    pub planet: Option<Planet>,
 }
 
 
-impl TryFrom<String> for PlanetSystemsCSV {
-    type Error = &'static str;
-
-    fn try_from(line: String) -> Result<Self, Self::Error> {
-        let line_split: Vec<String> = line.split(',')
-            .map(|x| x.to_string())
-            .collect();
-
-        if line_split.len() == 14 {
-            Ok(
-                PlanetSystemsCSV {
-                    name: line_split[0].to_string(),
-                    center_star: CenterStar {
-                        name: line_split[2].to_string(),
-                        mass: line_split[3].parse::<f32>().unwrap(),
-                        radius: line_split[4].parse::<f32>().unwrap(),
-                        effective_temperature: line_split[5].parse::<f32>().unwrap(),
-                    },
-                    planet: Planet {
-                        name: line_split[7].to_string(),
-                        mass: line_split[8].parse::<f32>().unwrap(),
-                        radius: line_split[9].parse::<f32>().unwrap(),
-                        semi_major_axis: line_split[10].parse::<f32>().unwrap(),
-                        eccentricity: line_split[11].parse::<f32>().unwrap(),
-                        orbital_period: line_split[12].parse::<f32>().unwrap(),
-                        moons: vec![],
-                    },
-                }
+/// Parses a row in the layout of `csv_columns::PLANET_SYSTEM_COLUMNS`. Rows with every planet column empty only
+/// give a star of the system, and empty cells are values that are not known.
+impl TryFrom<Vec<String>> for PlanetSystemsCSV {
+    type Error = LoadError;
+
+    fn try_from(line_split: Vec<String>) -> Result<Self, Self::Error> {
+        let measurement = |i: usize| parse_measurement(&line_split, &PLANET_SYSTEM_COLUMNS, i);
+
+        let center_star = CenterStar {
+            name: line_split[2].to_string(),
+            mass: measurement(3)?,
+            radius: measurement(4)?,
+            effective_temperature: measurement(5)?,
+            picture_url: line_split[6].to_string(),
+            provenance: Provenance::with_cells(&line_split, &[("mass", 3), ("radius", 4), ("effective_temperature", 5)]),
+        };
+
+        let planet = match line_split[7..].iter().all(|cell| cell.trim().is_empty()) {
+            true => None,
+            false => Some(Planet {
+                name: line_split[7].to_string(),
+                mass: measurement(8)?,
+                radius: measurement(9)?,
+                semi_major_axis: measurement(10)?,
+                eccentricity: parse_optional_cell(&line_split, &PLANET_SYSTEM_COLUMNS, 11)?,
+                orbital_period: measurement(12)?,
+                orbits: match line_split[14].trim() {
+                    "" | "0" => center_star.name.clone(),
+                    "1" => BARYCENTRE.to_string(),
+                    v => return Err(LoadError::invalid_value(PLANET_SYSTEM_COLUMNS[14], v, "Expected 0 or 1")),
+                },
+                picture_url: line_split[13].to_string(),
+                moons: vec![],
+                provenance: Provenance::with_cells(&line_split, &[
+                    ("mass", 8), ("radius", 9), ("semi_major_axis", 10), ("eccentricity", 11), ("orbital_period", 12),
+                ]),
+                inferred: Inferred::default(),
+            }),
+        };
+
+        Ok(
+            PlanetSystemsCSV {
+                name: line_split[0].to_string(),
+                picture_url: line_split[1].to_string(),
+                center_star,
+                planet,
+            }
+        )
+    }
+}
+
+impl PlanetSystemsCSV {
+    /// Whether `planet_system` has more than one star or a planet orbiting the barycentre, which can only be written
+    /// with `CIRCUMBINARY_COLUMN`. Files without such systems are written in the layout of `HEADER`, which is read by
+    /// older versions.
+    pub fn has_several_stars(planet_system: &PlanetSystem) -> bool {
+        planet_system.stars.len() > 1 || planet_system.planets.iter().any(|p| p.orbits.eq_ignore_ascii_case(BARYCENTRE))
+    }
+
+    /// Header of a file, with `CIRCUMBINARY_COLUMN` if `several_stars`.
+    pub fn header(several_stars: bool) -> String {
+        match several_stars {
+            true => format!("{},{}", HEADER, CIRCUMBINARY_COLUMN),
+            false => HEADER.to_string(),
+        }
+    }
+
+    /// One line per planet, with the star it orbits, as the CSV layout has no room for moons. Planets orbiting every
+    /// star are written with the primary star. Systems with more than one star, or without planets, start with a line
+    /// for every star, so all of them are kept in order.
+    pub fn from_system(planet_system: &PlanetSystem) -> Vec<PlanetSystemsCSV> {
+        let line = |center_star: &CenterStar, planet: Option<&Planet>| PlanetSystemsCSV {
+            name: planet_system.name.clone(),
+            picture_url: planet_system.picture_url.clone(),
+            center_star: center_star.clone(),
+            planet: planet.cloned(),
+        };
+
+        let primary_star = planet_system.stars.first().cloned().unwrap_or_default();
+
+        let star_lines = match (planet_system.stars.len(), planet_system.planets.len()) {
+            (1, n) if n > 0 => vec![],
+            _ => planet_system.stars.iter().map(|s| line(s, None)).collect(),
+        };
+
+        star_lines.into_iter()
+            .chain(planet_system.planets.iter()
+                .map(|p| line(planet_system.star(&p.orbits).unwrap_or(&primary_star), Some(p)))
             )
-        } else {
-            println!("{line_split:?}");
-            Err("Wrong size for input string. Size should be 9 after splitting on ','.")
+            .collect()
+    }
+
+    /// `CIRCUMBINARY_COLUMN` is only written if `several_stars`.
+    pub fn to_line(&self, several_stars: bool) -> String {
+        let star = [
+            self.name.clone(),
+            self.picture_url.clone(),
+            self.center_star.name.clone(),
+            self.center_star.provenance.format("mass", self.center_star.mass),
+            self.center_star.provenance.format("radius", self.center_star.radius),
+            self.center_star.provenance.format("effective_temperature", self.center_star.effective_temperature),
+            self.center_star.picture_url.clone(),
+        ];
+
+        let planet = match &self.planet {
+            Some(planet) => vec![
+                planet.name.clone(),
+                planet.provenance.format("mass", planet.mass),
+                planet.provenance.format("radius", planet.radius),
+                planet.provenance.format("semi_major_axis", planet.semi_major_axis),
+                planet.provenance.format("eccentricity", planet.eccentricity),
+                planet.provenance.format("orbital_period", planet.orbital_period),
+                planet.picture_url.clone(),
+                match planet.orbits.eq_ignore_ascii_case(BARYCENTRE) {
+                    true => "1".to_string(),
+                    false => String::new(),
+                },
+            ],
+            None => vec![String::new(); 8],
+        };
+
+        let mut cells = [star.to_vec(), planet].concat();
+
+        if !several_stars {
+            cells.pop();
         }
+
+        write_record(&cells)
     }
-}
\ No newline at end of file
+}
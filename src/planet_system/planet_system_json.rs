@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    planet_system::{
+        center_star::{CenterStar, BARYCENTRE},
+        consistency::Inferred,
+        hierarchy::{self, Link, Tree},
+        moon::Moon,
+        planet::Planet,
+        planet_system::PlanetSystem,
+        provenance::Provenance,
+        units::{self, LengthUnit},
+    },
+    util::number_format::format_optional,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetSystemJSON {
+    pub name: String,
+    pub center_star: CenterStarJSON,
+    /// The stars after the first, left out for systems with a single star
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub companion_stars: Vec<CenterStarJSON>,
+    #[serde(default)]
+    pub picture_url: String,
+    pub planets: Vec<PlanetJSON>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CenterStarJSON {
+    #[serde(rename = "type")]
+    pub body_type: String,
+    pub name: String,
+    /// Measurements that are not known are null or 0, see `measurement`
+    pub mass: Option<f64>,
+    pub radius: Option<f64>,
+    #[serde(default)]
+    pub picture_url: String,
+    /// Can be null, but not left out, as it tells stars apart from planets
+    #[serde(deserialize_with = "Option::deserialize")]
+    pub effective_temperature: Option<f64>,
+}
+
+/// Used for both planets and moons, the `type` tag tells them apart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetJSON {
+    #[serde(rename = "type")]
+    pub body_type: String,
+    pub name: String,
+    /// Measurements that are not known are null or 0, see `measurement`
+    pub mass: Option<f64>,
+    pub radius: Option<f64>,
+    #[serde(default)]
+    pub picture_url: String,
+    pub semi_major_axis: Option<f64>,
+    pub eccentricity: Option<f64>,
+    pub orbital_period: Option<f64>,
+    pub central_celestial_body: Box<CelestialBodyJSON>,
+}
+
+/// `value` of `field` as it was in the file, if it was not changed, see `Provenance::cell`.
+fn measurement(provenance: &Provenance, field: &str, value: Option<f64>) -> Option<f64> {
+    match provenance.cell(field, value) {
+        Some(cell) => cell.parse().ok(),
+        None => value,
+    }
+}
+
+/// Barycentre of every star of a system, orbited by circumbinary planets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarycentreJSON {
+    #[serde(rename = "type")]
+    pub body_type: String,
+    pub name: String,
+}
+
+/// The body a planet or moon orbits. Stars are tried first, as only they have an `effectiveTemperature`, and
+/// barycentres last, as they only have a type and a name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CelestialBodyJSON {
+    Star(CenterStarJSON),
+    Planet(PlanetJSON),
+    Barycentre(BarycentreJSON),
+}
+
+impl CelestialBodyJSON {
+    pub fn name(&self) -> &str {
+        match self {
+            CelestialBodyJSON::Star(star) => star.name.as_str(),
+            CelestialBodyJSON::Planet(planet) => planet.name.as_str(),
+            CelestialBodyJSON::Barycentre(barycentre) => barycentre.name.as_str(),
+        }
+    }
+}
+
+impl From<&CenterStar> for CenterStarJSON {
+    fn from(value: &CenterStar) -> Self {
+        CenterStarJSON {
+            body_type: "star".to_string(),
+            name: value.name.clone(),
+            mass: measurement(&value.provenance, "mass", value.mass),
+            radius: measurement(&value.provenance, "radius", value.radius),
+            picture_url: value.picture_url.clone(),
+            effective_temperature: measurement(&value.provenance, "effective_temperature", value.effective_temperature),
+        }
+    }
+}
+
+impl PlanetJSON {
+    /// Planets orbiting a star that is not in `planet_system` are written as orbiting its primary star.
+    fn from_planet(planet: &Planet, planet_system: &PlanetSystem) -> PlanetJSON {
+        let central_celestial_body = match planet.orbits.eq_ignore_ascii_case(BARYCENTRE) {
+            true => CelestialBodyJSON::Barycentre(BarycentreJSON {
+                body_type: "Barycentre".to_string(),
+                name: BARYCENTRE.to_string(),
+            }),
+            false => {
+                let star = planet_system.star(&planet.orbits).or(planet_system.stars.first());
+                CelestialBodyJSON::Star(CenterStarJSON::from(&star.cloned().unwrap_or_default()))
+            }
+        };
+
+        PlanetJSON {
+            body_type: "Planet".to_string(),
+            name: planet.name.clone(),
+            mass: measurement(&planet.provenance, "mass", planet.mass),
+            radius: measurement(&planet.provenance, "radius", planet.radius),
+            picture_url: planet.picture_url.clone(),
+            semi_major_axis: measurement(&planet.provenance, "semi_major_axis", planet.semi_major_axis),
+            eccentricity: measurement(&planet.provenance, "eccentricity", planet.eccentricity),
+            orbital_period: measurement(&planet.provenance, "orbital_period", planet.orbital_period),
+            central_celestial_body: Box::new(central_celestial_body),
+        }
+    }
+
+    fn from_moon(moon: &Moon, planet: &PlanetJSON) -> PlanetJSON {
+        PlanetJSON {
+            body_type: "Moon".to_string(),
+            name: moon.name.clone(),
+            mass: measurement(&moon.provenance, "mass", moon.mass),
+            radius: measurement(&moon.provenance, "radius", moon.radius),
+            picture_url: moon.picture_url.clone(),
+            semi_major_axis: measurement(&moon.provenance, "semi_major_axis", moon.semi_major_axis),
+            eccentricity: measurement(&moon.provenance, "eccentricity", moon.eccentricity),
+            orbital_period: measurement(&moon.provenance, "orbital_period", moon.orbital_period),
+            central_celestial_body: Box::new(CelestialBodyJSON::Planet(planet.clone())),
+        }
+    }
+}
+
+/// Moons are written after all planets, each after the body it orbits, with that body as its `centralCelestialBody`.
+impl From<&PlanetSystem> for PlanetSystemJSON {
+    fn from(value: &PlanetSystem) -> Self {
+        let planets: Vec<PlanetJSON> = value.planets.iter()
+            .map(|p| PlanetJSON::from_planet(p, value))
+            .collect();
+
+        let mut moons: Vec<PlanetJSON> = vec![];
+
+        for (planet, json) in value.planets.iter().zip(planets.iter()) {
+            moons_json(&planet.moons, json, &mut moons);
+        }
+
+        let mut stars = value.stars.iter().map(CenterStarJSON::from);
+
+        PlanetSystemJSON {
+            name: value.name.clone(),
+            center_star: stars.next().unwrap_or_else(|| CenterStarJSON::from(&CenterStar::default())),
+            companion_stars: stars.collect(),
+            picture_url: value.picture_url.clone(),
+            planets: planets.into_iter().chain(moons).collect(),
+        }
+    }
+}
+
+/// Pushes every moon of `moons` orbiting `central` to `json`, followed by the moons orbiting it.
+fn moons_json(moons: &[Moon], central: &PlanetJSON, json: &mut Vec<PlanetJSON>) {
+    for moon in moons {
+        let moon_json = PlanetJSON::from_moon(moon, central);
+        json.push(moon_json.clone());
+        moons_json(&moon.moons, &moon_json, json);
+    }
+}
+
+/// Provenance keeping `values` as cells, as serde does not give the text of the numbers.
+fn provenance(values: &[(&'static str, Option<f64>)]) -> Provenance {
+    Provenance {
+        cells: values.iter().map(|(field, value)| (*field, format_optional(*value))).collect(),
+        ..Provenance::default()
+    }
+}
+
+impl From<CenterStarJSON> for CenterStar {
+    fn from(value: CenterStarJSON) -> Self {
+        CenterStar {
+            name: value.name,
+            mass: units::measurement(value.mass),
+            radius: units::measurement(value.radius),
+            effective_temperature: units::measurement(value.effective_temperature),
+            picture_url: value.picture_url,
+            provenance: provenance(&[
+                ("mass", value.mass), ("radius", value.radius), ("effective_temperature", value.effective_temperature),
+            ]),
+        }
+    }
+}
+
+impl From<PlanetJSON> for Planet {
+    fn from(value: PlanetJSON) -> Self {
+        Planet {
+            name: value.name,
+            mass: units::measurement(value.mass),
+            radius: units::measurement(value.radius),
+            semi_major_axis: units::measurement(value.semi_major_axis),
+            eccentricity: value.eccentricity,
+            orbital_period: units::measurement(value.orbital_period),
+            orbits: value.central_celestial_body.name().to_string(),
+            picture_url: value.picture_url,
+            moons: vec![],
+            provenance: provenance(&[
+                ("mass", value.mass), ("radius", value.radius), ("semi_major_axis", value.semi_major_axis),
+                ("eccentricity", value.eccentricity), ("orbital_period", value.orbital_period),
+            ]),
+            inferred: Inferred::default(),
+        }
+    }
+}
+
+impl From<PlanetJSON> for Moon {
+    fn from(value: PlanetJSON) -> Self {
+        Moon {
+            name: value.name,
+            mass: units::measurement(value.mass),
+            radius: units::measurement(value.radius),
+            semi_major_axis: units::measurement(value.semi_major_axis),
+            eccentricity: value.eccentricity,
+            orbital_period: units::measurement(value.orbital_period),
+            picture_url: value.picture_url,
+            moons: vec![],
+            provenance: provenance(&[
+                ("mass", value.mass), ("radius", value.radius), ("semi_major_axis", value.semi_major_axis),
+                ("eccentricity", value.eccentricity), ("orbital_period", value.orbital_period),
+            ]),
+            inferred: Inferred::default(),
+        }
+    }
+}
+
+fn moons_of(trees: Vec<Tree<PlanetJSON>>) -> Vec<Moon> {
+    trees.into_iter()
+        .map(|tree| {
+            let satellites = tree.satellites;
+            let mut moon = Moon::from(tree.body);
+            moon.moons = moons_of(satellites);
+            moon
+        })
+        .collect()
+}
+
+/// Every body orbiting a star of the system or its barycentre is a planet, and every body orbiting a planet or moon
+/// a moon, no matter its `type`. Systems with a body orbiting a body that is not in the system, or orbiting itself
+/// through other bodies, are not loaded.
+impl TryFrom<PlanetSystemJSON> for PlanetSystem {
+    type Error = String;
+
+    fn try_from(value: PlanetSystemJSON) -> Result<Self, Self::Error> {
+        let stars: Vec<CenterStar> = [value.center_star].into_iter()
+            .chain(value.companion_stars)
+            .map(CenterStar::from)
+            .collect();
+
+        // Bodies orbiting a star of the system or the barycentre are planets, and the bodies orbiting them moons
+        let links: Vec<Link<PlanetJSON>> = value.planets.into_iter()
+            .map(|body| {
+                let central = match body.central_celestial_body.as_ref() {
+                    CelestialBodyJSON::Star(star) if stars.iter().any(|s| s.name == star.name) => None,
+                    CelestialBodyJSON::Barycentre(barycentre) if barycentre.body_type.eq_ignore_ascii_case("barycentre") => None,
+                    central => Some(central.name().to_string()),
+                };
+
+                Link { name: body.name.clone(), central, body }
+            })
+            .collect();
+
+        let names: Vec<(String, String)> = links.iter()
+            .map(|l| (l.name.clone(), l.central.clone().unwrap_or_default()))
+            .collect();
+
+        let (trees, errors) = hierarchy::resolve(links, |_, _| Ok(()));
+
+        if let Some((i, error)) = errors.first() {
+            return Err(format!("'{}' orbits '{}' in '{}': {}", names[*i].0, names[*i].1, value.name, error));
+        }
+
+        let planets: Vec<Planet> = trees.into_iter()
+            .map(|tree| {
+                let barycentre = matches!(tree.body.central_celestial_body.as_ref(), CelestialBodyJSON::Barycentre(_));
+                let mut planet = Planet::from(tree.body);
+
+                if barycentre {
+                    planet.orbits = BARYCENTRE.to_string();
+                }
+
+                planet.moons = moons_of(tree.satellites);
+                planet
+            })
+            .collect();
+
+        Ok(PlanetSystem {
+            name: value.name,
+            picture_url: value.picture_url,
+            stars,
+            planets,
+            semi_major_axis_unit: LengthUnit::Kilometre,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+
+use crate::{planet_system::units, util::number_format::format_optional};
+
+/// Where a body was loaded from, kept so problems with it can be traced back to the source file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Provenance {
+    pub file: String,
+    pub line: Option<usize>,
+    /// Cells the measurements of the body were read from, by field
+    pub cells: Vec<(&'static str, String)>,
+}
+
+impl Provenance {
+    pub fn new(file: &str, line: Option<usize>) -> Provenance {
+        Provenance {
+            file: file.to_string(),
+            line,
+            cells: vec![],
+        }
+    }
+
+    /// Provenance keeping the cell of `row` at the index given for each field.
+    pub fn with_cells(row: &[String], fields: &[(&'static str, usize)]) -> Provenance {
+        Provenance {
+            cells: fields.iter().map(|(field, i)| (*field, row[*i].clone())).collect(),
+            ..Provenance::default()
+        }
+    }
+
+    /// Sets the file and line the body was loaded from, keeping its cells.
+    pub fn locate(&mut self, file: &str, line: Option<usize>) {
+        self.file = file.to_string();
+        self.line = line;
+    }
+
+    /// Cell `field` was read from, if it gives `value`, in the units of the file.
+    pub fn cell(&self, field: &str, value: Option<f64>) -> Option<&str> {
+        self.cells.iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, cell)| cell.as_str())
+            .filter(|cell| units::cell_value(field, cell) == value)
+    }
+
+    /// Value `field` had in the file, see `SourceUnits::denormalised`.
+    pub fn source_value(&self, field: &str) -> Option<f64> {
+        self.cells.iter()
+            .find(|(f, _)| *f == field)
+            .and_then(|(_, cell)| units::cell_value(field, cell))
+    }
+
+    /// Formats `value` of `field` as `format_optional` does, or as the cell it was read from if it was not changed,
+    /// as converting a value back and forth can leave it off in its last bits.
+    pub fn format(&self, field: &str, value: Option<f64>) -> String {
+        match self.cell(field, value) {
+            Some(cell) => cell.to_string(),
+            None => format_optional(value),
+        }
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}", self.file, line),
+            None => write!(f, "{}", self.file),
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use std::error::Error;
+
+use crate::{
+    planet_system::{
+        moon::Moon,
+        planet_system::PlanetSystem,
+        provenance::Provenance,
+    },
+    util::number_format::{format_number, format_rounded},
+};
+
+/// Significant digits of values shown in other units than those of the model.
+const DISPLAY_DIGITS: usize = 7;
+
+/// Fields of center stars, planets and moons that are `None` if they are not known.
+pub const MEASUREMENTS: [&str; 6] = ["mass", "radius", "semi_major_axis", "eccentricity", "orbital_period", "effective_temperature"];
+
+/// Measurement as read from a file, `None` if it is not known. The catalogue files write 0 for values the archive
+/// does not have, so 0 is read as not known, and this is only used for values that can not be 0, like masses.
+pub fn measurement(value: Option<f64>) -> Option<f64> {
+    value.filter(|v| *v != 0.0)
+}
+
+/// Value of `field` given by `cell` of a file, read as the loaders read it.
+pub fn cell_value(field: &str, cell: &str) -> Option<f64> {
+    let value = cell.trim().parse::<f64>().ok();
+
+    match field {
+        "eccentricity" => value,
+        _ => measurement(value),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MassUnit {
+    Kilogram,
+    EarthMass,
+    JupiterMass,
+    SolarMass,
+    /// Unit of the moon masses in files/csv/solarSystem.csv, which are π r³ ρ in 10^12 kg,
+    /// missing the 4/3 of the volume of a sphere
+    SolarSystemMoonMass,
+}
+
+impl MassUnit {
+    pub fn kilograms(&self) -> f64 {
+        match self {
+            MassUnit::Kilogram => 1.0,
+            MassUnit::EarthMass => 5.9722e24,
+            MassUnit::JupiterMass => 1.89813e27,
+            MassUnit::SolarMass => 1.98847e30,
+            MassUnit::SolarSystemMoonMass => 4.0 / 3.0 * 1e12,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            MassUnit::Kilogram => "kg",
+            MassUnit::EarthMass => "M⊕",
+            MassUnit::JupiterMass => "MJ",
+            MassUnit::SolarMass => "M☉",
+            MassUnit::SolarSystemMoonMass => "4/3·10¹² kg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LengthUnit {
+    #[default]
+    Kilometre,
+    AstronomicalUnit,
+    EarthRadius,
+    JupiterRadius,
+    SolarRadius,
+}
+
+impl LengthUnit {
+    pub fn kilometres(&self) -> f64 {
+        match self {
+            LengthUnit::Kilometre => 1.0,
+            LengthUnit::AstronomicalUnit => 1.495978707e8,
+            LengthUnit::EarthRadius => 6371.0,
+            LengthUnit::JupiterRadius => 69911.0,
+            LengthUnit::SolarRadius => 695700.0,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Kilometre => "km",
+            LengthUnit::AstronomicalUnit => "AU",
+            LengthUnit::EarthRadius => "R⊕",
+            LengthUnit::JupiterRadius => "RJ",
+            LengthUnit::SolarRadius => "R☉",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeUnit {
+    Second,
+    Day,
+    /// Julian year of 365.25 days
+    Year,
+}
+
+impl TimeUnit {
+    pub fn days(&self) -> f64 {
+        match self {
+            TimeUnit::Second => 1.0 / 86400.0,
+            TimeUnit::Day => 1.0,
+            TimeUnit::Year => 365.25,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TimeUnit::Second => "s",
+            TimeUnit::Day => "d",
+            TimeUnit::Year => "yr",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Mass(f64);
+
+impl Mass {
+    pub fn new(value: f64, unit: MassUnit) -> Mass {
+        Mass(value * unit.kilograms())
+    }
+
+    pub fn kg(&self) -> f64 {
+        self.0
+    }
+
+    pub fn get(&self, unit: MassUnit) -> f64 {
+        self.0 / unit.kilograms()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Length(f64);
+
+impl Length {
+    pub fn new(value: f64, unit: LengthUnit) -> Length {
+        Length(value * unit.kilometres())
+    }
+
+    pub fn from_m(metres: f64) -> Length {
+        Length(metres / 1000.0)
+    }
+
+    pub fn km(&self) -> f64 {
+        self.0
+    }
+
+    pub fn m(&self) -> f64 {
+        self.0 * 1000.0
+    }
+
+    pub fn get(&self, unit: LengthUnit) -> f64 {
+        self.0 / unit.kilometres()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Time(f64);
+
+impl Time {
+    pub fn new(value: f64, unit: TimeUnit) -> Time {
+        Time(value * unit.days())
+    }
+
+    pub fn from_days(days: f64) -> Time {
+        Time(days)
+    }
+
+    pub fn from_seconds(seconds: f64) -> Time {
+        Time(seconds * TimeUnit::Second.days())
+    }
+
+    pub fn days(&self) -> f64 {
+        self.0
+    }
+
+    pub fn seconds(&self) -> f64 {
+        self.0 * 86400.0
+    }
+
+    pub fn get(&self, unit: TimeUnit) -> f64 {
+        self.0 / unit.days()
+    }
+}
+
+/// Units of the values of one kind of body in a file. Stars have no orbit, so their semi-major axis and orbital
+/// period units are not used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyUnits {
+    pub mass: MassUnit,
+    pub radius: LengthUnit,
+    pub semi_major_axis: LengthUnit,
+    pub orbital_period: TimeUnit,
+}
+
+impl BodyUnits {
+    /// Units the model stores its values in: masses in kg, radii and semi-major axes in km and orbital periods in days.
+    /// Loaders convert the values of a file from its `SourceUnits` into these units, and writers convert them back.
+    pub const MODEL: BodyUnits = BodyUnits {
+        mass: MassUnit::Kilogram,
+        radius: LengthUnit::Kilometre,
+        semi_major_axis: LengthUnit::Kilometre,
+        orbital_period: TimeUnit::Day,
+    };
+
+    /// Size of the unit of a field of a center star, planet or moon in the units of the model, and its symbol.
+    /// `None` for fields without a unit.
+    pub fn field_unit(&self, field: &str) -> Option<(f64, &'static str)> {
+        let model = BodyUnits::MODEL;
+
+        match field {
+            "mass" => Some((self.mass.kilograms() / model.mass.kilograms(), self.mass.symbol())),
+            "radius" => Some((self.radius.kilometres() / model.radius.kilometres(), self.radius.symbol())),
+            "semi_major_axis" => Some((self.semi_major_axis.kilometres() / model.semi_major_axis.kilometres(), self.semi_major_axis.symbol())),
+            "orbital_period" => Some((self.orbital_period.days() / model.orbital_period.days(), self.orbital_period.symbol())),
+            "effective_temperature" => Some((1.0, "K")),
+            _ => None,
+        }
+    }
+
+    /// Formats `value`, a value of `field` in the units of the model, in these units and with the symbol of its unit.
+    /// Values converted into other units are rounded to `DISPLAY_DIGITS` significant digits, and values that are not
+    /// known are shown as "—".
+    pub fn display(&self, field: &str, value: Option<f64>) -> String {
+        match (self.field_unit(field), value) {
+            (_, None) => "—".to_string(),
+            (Some((size, symbol)), Some(value)) if size != 1.0 => format!("{} {}", format_rounded(value / size, DISPLAY_DIGITS), symbol),
+            (Some((_, symbol)), Some(value)) => format!("{} {}", format_number(value), symbol),
+            (None, Some(value)) => format_number(value),
+        }
+    }
+
+    /// Same as `display`, for a value as given by `FieldEditable::get_fields`, where measurements that are not known
+    /// are empty. Values that are not numbers are returned as they are.
+    pub fn display_field(&self, field: &str, value: &str) -> String {
+        match (self.field_unit(field), value.parse::<f64>()) {
+            _ if value.is_empty() && MEASUREMENTS.contains(&field) => self.display(field, None),
+            (Some(_), Ok(number)) => self.display(field, Some(number)),
+            _ => value.to_string(),
+        }
+    }
+
+    /// Converts `input`, a value of `field` in these units, into a value for `FieldEditable::edit_field`. An empty
+    /// input clears a measurement.
+    pub fn field_input(&self, field: &str, input: &str) -> Result<String, Box<dyn Error>> {
+        match self.field_unit(field) {
+            Some(_) if input.trim().is_empty() => Ok(String::new()),
+            Some((size, _)) => Ok((input.trim().parse::<f64>()? * size).to_string()),
+            None => Ok(input.to_string()),
+        }
+    }
+}
+
+/// Units values are shown in by the user interface.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnitSystem {
+    /// The units of the model: kg, km and days
+    #[default]
+    Si,
+    Earth,
+    Jupiter,
+    Solar,
+}
+
+impl UnitSystem {
+    pub fn next(&self) -> UnitSystem {
+        match self {
+            UnitSystem::Si => UnitSystem::Earth,
+            UnitSystem::Earth => UnitSystem::Jupiter,
+            UnitSystem::Jupiter => UnitSystem::Solar,
+            UnitSystem::Solar => UnitSystem::Si,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnitSystem::Si => "SI",
+            UnitSystem::Earth => "Earth",
+            UnitSystem::Jupiter => "Jupiter",
+            UnitSystem::Solar => "Solar",
+        }
+    }
+
+    /// Masses and radii relative to the body the system is named after, with orbits in AU and years.
+    pub fn body_units(&self) -> BodyUnits {
+        let relative = |mass, radius| BodyUnits {
+            mass,
+            radius,
+            semi_major_axis: LengthUnit::AstronomicalUnit,
+            orbital_period: TimeUnit::Year,
+        };
+
+        match self {
+            UnitSystem::Si => BodyUnits::MODEL,
+            UnitSystem::Earth => relative(MassUnit::EarthMass, LengthUnit::EarthRadius),
+            UnitSystem::Jupiter => relative(MassUnit::JupiterMass, LengthUnit::JupiterRadius),
+            UnitSystem::Solar => relative(MassUnit::SolarMass, LengthUnit::SolarRadius),
+        }
+    }
+}
+
+fn normalised(value: f64, unit: f64, model: f64) -> f64 {
+    value * unit / model
+}
+
+/// Planet systems of the catalogue files with semi-major axes in AU, as their rows were copied from
+/// files/csv/solarSystem.csv. Every other system is in km.
+const CATALOGUE_AU_SYSTEMS: [&str; 1] = ["Solar System"];
+
+/// Unit of the semi-major axes of the planet system `name` in the catalogue files.
+pub fn catalogue_semi_major_axis_unit(name: &str) -> LengthUnit {
+    match CATALOGUE_AU_SYSTEMS.contains(&name) {
+        true => LengthUnit::AstronomicalUnit,
+        false => LengthUnit::Kilometre,
+    }
+}
+
+/// Units the values of a file are written in, declared by the loader and writer of each file format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceUnits {
+    pub star: BodyUnits,
+    pub planet: BodyUnits,
+    pub moon: BodyUnits,
+}
+
+impl SourceUnits {
+    /// files/csv/planets.csv and files/json/planets_100.json, which are in the units of the model except for the
+    /// semi-major axes of some planet systems, see `catalogue`
+    pub const PLANET_SYSTEMS: SourceUnits = SourceUnits {
+        star: BodyUnits::MODEL,
+        planet: BodyUnits::MODEL,
+        moon: BodyUnits::MODEL,
+    };
+
+    /// files/csv/solarSystem.csv, with semi-major axes in AU and moon masses on their own scale
+    pub const SOLAR_SYSTEM: SourceUnits = SourceUnits {
+        star: BodyUnits::MODEL,
+        planet: BodyUnits {
+            semi_major_axis: LengthUnit::AstronomicalUnit,
+            ..BodyUnits::MODEL
+        },
+        moon: BodyUnits {
+            mass: MassUnit::SolarSystemMoonMass,
+            ..BodyUnits::MODEL
+        },
+    };
+
+    /// Units of a planet system in the catalogue files, with the semi-major axes of its planets in `semi_major_axis`,
+    /// see `catalogue_semi_major_axis_unit`.
+    pub fn catalogue(semi_major_axis: LengthUnit) -> SourceUnits {
+        SourceUnits {
+            planet: BodyUnits {
+                semi_major_axis,
+                ..BodyUnits::MODEL
+            },
+            ..SourceUnits::PLANET_SYSTEMS
+        }
+    }
+
+    /// Copies of `planet_systems` converted into the units each of them had in the catalogue file it was loaded from,
+    /// see `PlanetSystem::semi_major_axis_unit`.
+    pub fn denormalised_catalogue(planet_systems: &[PlanetSystem]) -> Vec<PlanetSystem> {
+        planet_systems.iter()
+            .cloned()
+            .map(|mut planet_system| {
+                SourceUnits::catalogue(planet_system.semi_major_axis_unit).denormalise(&mut planet_system);
+                planet_system
+            })
+            .collect()
+    }
+
+    /// Converts every value of `planet_system` from these units into the units of the model.
+    pub fn normalise(&self, planet_system: &mut PlanetSystem) {
+        self.convert(planet_system, |_, _, value, unit, model| normalised(value, unit, model));
+    }
+
+    /// Copies of `planet_systems` with every value converted from the units of the model into these units.
+    pub fn denormalised(&self, planet_systems: &[PlanetSystem]) -> Vec<PlanetSystem> {
+        planet_systems.iter()
+            .cloned()
+            .map(|mut planet_system| {
+                self.denormalise(&mut planet_system);
+                planet_system
+            })
+            .collect()
+    }
+
+    fn denormalise(&self, planet_system: &mut PlanetSystem) {
+        self.convert(planet_system, |provenance, field, value, unit, model| match provenance.source_value(field) {
+            Some(source) if normalised(source, unit, model) == value => source,
+            _ => value * model / unit,
+        });
+    }
+
+    /// Replaces every value with the result of `scale`, which is given the provenance of its body, its field, and the
+    /// value and the size of its unit in this file and in the model.
+    fn convert(&self, planet_system: &mut PlanetSystem, scale: impl Fn(&Provenance, &'static str, f64, f64, f64) -> f64) {
+        let model = BodyUnits::MODEL;
+        // Values that are not known are left as they are
+        let mass = |provenance: &Provenance, value: &mut Option<f64>, unit: MassUnit| if let Some(value) = value {
+            *value = scale(provenance, "mass", *value, unit.kilograms(), model.mass.kilograms());
+        };
+        let length = |provenance: &Provenance, field: &'static str, value: &mut Option<f64>, unit: LengthUnit, model: LengthUnit| if let Some(value) = value {
+            *value = scale(provenance, field, *value, unit.kilometres(), model.kilometres());
+        };
+        let time = |provenance: &Provenance, value: &mut Option<f64>, unit: TimeUnit| if let Some(value) = value {
+            *value = scale(provenance, "orbital_period", *value, unit.days(), model.orbital_period.days());
+        };
+
+        for star in planet_system.stars.iter_mut() {
+            mass(&star.provenance, &mut star.mass, self.star.mass);
+            length(&star.provenance, "radius", &mut star.radius, self.star.radius, model.radius);
+        }
+
+        for planet in planet_system.planets.iter_mut() {
+            mass(&planet.provenance, &mut planet.mass, self.planet.mass);
+            length(&planet.provenance, "radius", &mut planet.radius, self.planet.radius, model.radius);
+            length(&planet.provenance, "semi_major_axis", &mut planet.semi_major_axis, self.planet.semi_major_axis, model.semi_major_axis);
+            time(&planet.provenance, &mut planet.orbital_period, self.planet.orbital_period);
+
+            // Moons of moons are in the units of moons
+            let mut moons: Vec<&mut Moon> = planet.moons.iter_mut().collect();
+
+            while let Some(moon) = moons.pop() {
+                mass(&moon.provenance, &mut moon.mass, self.moon.mass);
+                length(&moon.provenance, "radius", &mut moon.radius, self.moon.radius, model.radius);
+                length(&moon.provenance, "semi_major_axis", &mut moon.semi_major_axis, self.moon.semi_major_axis, model.semi_major_axis);
+                time(&moon.provenance, &mut moon.orbital_period, self.moon.orbital_period);
+                moons.extend(moon.moons.iter_mut());
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::{
+    collections::HashMap,
+    fmt,
+};
+
+use crate::{
+    planet_system::{
+        celestial_body::{CelestialBody, OrbitingBody},
+        consistency,
+        load_error::LoadError,
+        planet_system::PlanetSystem,
+        provenance::Provenance,
+    },
+    util::number_format::format_number,
+};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Problem with a body, pointing out where in the file the body was loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub provenance: Provenance,
+    /// The kind and name of the body, like "planet 'Earth'"
+    pub body: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}: ", self.provenance, self.severity)?;
+
+        if !self.body.is_empty() {
+            write!(f, "{}: ", self.body)?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Rows that could not be loaded are errors, without a body as there is none.
+impl From<LoadError> for Diagnostic {
+    fn from(value: LoadError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            provenance: Provenance::new(&value.file, value.line),
+            body: String::new(),
+            message: match (value.column, value.value) {
+                (Some(column), Some(v)) => format!("column '{}' = '{}': {}", column, v, value.message),
+                _ => value.message,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidateOptions {
+    /// How far off an orbital period may be from the period given by Kepler's third law, relative to that period.
+    pub period_tolerance: f64,
+}
+
+impl Default for ValidateOptions {
+    fn default() -> Self {
+        ValidateOptions {
+            period_tolerance: 0.1,
+        }
+    }
+}
+
+/// Body the rules are run for.
+struct Subject<'a> {
+    provenance: &'a Provenance,
+    /// The kind and name of the body, like "planet 'Earth'"
+    body: String,
+}
+
+impl<'a> Subject<'a> {
+    fn of(body: &'a dyn CelestialBody) -> Subject<'a> {
+        Subject { provenance: body.provenance(), body: body.describe() }
+    }
+}
+
+struct Validator<'a> {
+    options: &'a ValidateOptions,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, severity: Severity, provenance: &Provenance, body: &str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            provenance: provenance.clone(),
+            body: body.to_string(),
+            message,
+        });
+    }
+
+    /// Values that are not known, including the 0 files use for them, are missing. Other values must be positive.
+    fn positive(&mut self, subject: &Subject, field: &str, value: Option<f64>) {
+        match value {
+            None => self.report(Severity::Warning, subject.provenance, &subject.body, format!("{} is missing", field)),
+            Some(value) if value <= 0.0 || !value.is_finite() => {
+                self.report(Severity::Error, subject.provenance, &subject.body, format!("{} is {}, it must be positive", field, value));
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the values every kind of body has.
+    fn body(&mut self, body: &dyn CelestialBody) {
+        let subject = Subject::of(body);
+
+        self.positive(&subject, "mass", body.mass().map(|m| m.kg()));
+        self.positive(&subject, "radius", body.radius().map(|r| r.km()));
+    }
+
+    /// Checks the shape of an orbit, and its period against the period given by Kepler's third law for bodies
+    /// orbiting `central`.
+    fn orbit(&mut self, body: &dyn OrbitingBody, central: &dyn CelestialBody) {
+        let subject = Subject::of(body);
+        let (semi_major_axis, eccentricity, orbital_period) = (body.semi_major_axis(), body.eccentricity(), body.orbital_period());
+
+        match eccentricity {
+            Some(eccentricity) if !(0.0..1.0).contains(&eccentricity) => {
+                self.report(Severity::Error, subject.provenance, &subject.body, format!("eccentricity is {}, it must be in [0, 1)", eccentricity));
+            }
+            _ => {}
+        }
+
+        self.positive(&subject, "semi-major axis", semi_major_axis.map(|a| a.km()));
+        self.positive(&subject, "orbital period", orbital_period.map(|p| p.days()));
+
+        let (semi_major_axis, orbital_period, central_mass) = match (semi_major_axis, orbital_period, central.mass()) {
+            (Some(a), Some(p), Some(m)) if a.km() > 0.0 && p.days() > 0.0 && m.kg() > 0.0 => (a, p, m),
+            _ => return,
+        };
+
+        let expected = consistency::orbital_period(semi_major_axis, body.mass(), central_mass).days();
+
+        if ((orbital_period.days() - expected) / expected).abs() > self.options.period_tolerance {
+            self.report(Severity::Warning, subject.provenance, &subject.body, format!(
+                "orbital period is {} days, but Kepler's third law gives {} days for its semi-major axis",
+                format_number(orbital_period.days()), format_number(expected)
+            ));
+        }
+    }
+
+    /// Reports every name that is used for more than one body, at every body after the first.
+    fn unique_names(&mut self, bodies: Vec<(&Provenance, String, &str)>) {
+        let mut first: HashMap<&str, &Provenance> = HashMap::new();
+
+        for (provenance, body, name) in bodies {
+            match first.get(name) {
+                Some(first) => self.report(Severity::Error, provenance, &body, format!("name is also used at {}", first)),
+                None => {
+                    first.insert(name, provenance);
+                }
+            }
+        }
+    }
+}
+
+/// Runs every rule over the stars, planets and moons of `planet_systems`.
+/// Diagnostics are sorted by the file and line of the body they are for.
+pub fn validate(planet_systems: &[PlanetSystem], options: &ValidateOptions) -> Vec<Diagnostic> {
+    let mut validator = Validator { options, diagnostics: vec![] };
+
+    for planet_system in planet_systems {
+        for star in &planet_system.stars {
+            validator.body(star);
+            validator.positive(&Subject::of(star), "effective temperature", star.effective_temperature);
+        }
+
+        for planet in &planet_system.planets {
+            validator.body(planet);
+
+            match planet_system.host(planet) {
+                Some(star) => validator.orbit(planet, star.as_ref()),
+                None => {
+                    let subject = Subject::of(planet);
+                    validator.report(Severity::Error, subject.provenance, &subject.body, format!(
+                        "orbits '{}', which is not a star of planet system '{}'", planet.orbits, planet_system.name
+                    ));
+                }
+            }
+
+            for (central, moon) in planet.all_moons() {
+                validator.body(moon);
+                validator.orbit(moon, central);
+            }
+        }
+    }
+
+    // Planet systems are loaded from the rows of their stars
+    validator.unique_names(planet_systems.iter()
+        .filter_map(|ps| Some((&ps.stars.first()?.provenance, format!("planet system '{}'", ps.name), ps.name.as_str())))
+        .collect()
+    );
+
+    validator.unique_names(planet_systems.iter()
+        .flat_map(|ps| ps.bodies())
+        .map(|body| (body.provenance(), body.describe(), body.name()))
+        .collect()
+    );
+
+    let mut diagnostics = validator.diagnostics;
+    diagnostics.sort_by(|a, b| (&a.provenance.file, a.provenance.line).cmp(&(&b.provenance.file, b.provenance.line)));
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::planet_system::{center_star::CenterStar, planet::Planet};
+
+    /// The Sun and the Earth, in km, kg and days
+    fn solar_system() -> PlanetSystem {
+        PlanetSystem {
+            name: "Solar System".to_string(),
+            stars: vec![CenterStar {
+                name: "Sun".to_string(),
+                mass: Some(1.9885e30),
+                radius: Some(695342.0),
+                effective_temperature: Some(5777.0),
+                provenance: Provenance::new("planets.csv", Some(1)),
+                ..CenterStar::default()
+            }],
+            planets: vec![Planet {
+                name: "Earth".to_string(),
+                mass: Some(5.972e24),
+                radius: Some(6371.0),
+                semi_major_axis: Some(1.496e8),
+                eccentricity: Some(0.017),
+                orbital_period: Some(365.0),
+                provenance: Provenance::new("planets.csv", Some(2)),
+                ..Planet::default()
+            }],
+            ..PlanetSystem::default()
+        }
+    }
+
+    /// Severity, body and message of every diagnostic
+    fn diagnostics(planet_system: PlanetSystem) -> Vec<(Severity, String, String)> {
+        validate(&[planet_system], &ValidateOptions::default()).into_iter()
+            .map(|d| (d.severity, d.body, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_system() {
+        assert_eq!(diagnostics(solar_system()), vec![]);
+    }
+
+    #[test]
+    fn rejects_values_that_are_not_positive() {
+        let mut planet_system = solar_system();
+        planet_system.planets[0].radius = Some(-1.0);
+
+        assert_eq!(diagnostics(planet_system), vec![
+            (Severity::Error, "planet 'Earth'".to_string(), "radius is -1, it must be positive".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn warns_about_values_that_are_not_known() {
+        let mut planet_system = solar_system();
+        planet_system.planets[0].mass = None;
+        planet_system.stars[0].effective_temperature = None;
+
+        assert_eq!(diagnostics(planet_system), vec![
+            (Severity::Warning, "star 'Sun'".to_string(), "effective temperature is missing".to_string()),
+            (Severity::Warning, "planet 'Earth'".to_string(), "mass is missing".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn rejects_eccentricity_outside_of_closed_orbits() {
+        let mut planet_system = solar_system();
+        planet_system.planets[0].eccentricity = Some(1.0);
+
+        let diagnostics = diagnostics(planet_system);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, Severity::Error);
+        assert!(diagnostics[0].2.starts_with("eccentricity is 1"));
+    }
+
+    #[test]
+    fn warns_about_periods_off_from_keplers_third_law() {
+        let mut planet_system = solar_system();
+        planet_system.planets[0].orbital_period = Some(100.0);
+
+        let diagnostics = diagnostics(planet_system);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, Severity::Warning);
+        assert!(diagnostics[0].2.starts_with("orbital period is 100"));
+    }
+
+    #[test]
+    fn rejects_names_used_twice() {
+        let mut planet_system = solar_system();
+        planet_system.planets[0].name = "Sun".to_string();
+
+        assert_eq!(diagnostics(planet_system), vec![
+            (Severity::Error, "planet 'Sun'".to_string(), "name is also used at planets.csv:1".to_string()),
+        ]);
+    }
+}
@@ -0,0 +1,156 @@
+/// Fields of a record, together with the line the record starts on.
+pub type Record = (usize, Vec<String>);
+
+/// Splits CSV text into records as described in RFC 4180.
+///
+/// Fields can be quoted with '"', in which case they may contain ',', line breaks and '""' for a single '"'.
+/// Records end with either "\n" or "\r\n", and a UTF-8 BOM at the start of the text is ignored.
+/// Every record is returned with the line it starts on, and errors with the line of the record they are in.
+pub fn parse_records(text: &str) -> Result<Vec<Record>, (usize, String)> {
+    let mut records: Vec<Record> = vec![];
+    let mut record: Vec<String> = vec![];
+    let mut field = String::new();
+
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut quoted = false;
+    let mut chars = text.strip_prefix('\u{feff}').unwrap_or(text).chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => {
+                if c == '\n' {
+                    line += 1;
+                }
+
+                field.push(c);
+            }
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+
+                line += 1;
+                record_line = line;
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err((record_line, "Unterminated quoted field".to_string()));
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    Ok(records)
+}
+
+/// Joins `fields` into a single record, quoting the fields that contain ',', '"' or line breaks.
+pub fn write_record(fields: &[String]) -> String {
+    fields.iter()
+        .map(|field| if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.clone()
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(record: &[&str]) -> Vec<String> {
+        record.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_plain_records() {
+        assert_eq!(parse_records("a,b,c\n1,,3\n").unwrap(), vec![
+            (1, fields(&["a", "b", "c"])),
+            (2, fields(&["1", "", "3"])),
+        ]);
+    }
+
+    #[test]
+    fn parses_last_record_without_line_break() {
+        assert_eq!(parse_records("a,b\n1,2").unwrap(), vec![
+            (1, fields(&["a", "b"])),
+            (2, fields(&["1", "2"])),
+        ]);
+    }
+
+    #[test]
+    fn parses_quoted_fields() {
+        assert_eq!(parse_records("\"a,b\",c\n").unwrap(), vec![(1, fields(&["a,b", "c"]))]);
+    }
+
+    #[test]
+    fn parses_escaped_quotes() {
+        assert_eq!(parse_records("\"say \"\"hi\"\"\",x\n").unwrap(), vec![(1, fields(&["say \"hi\"", "x"]))]);
+    }
+
+    #[test]
+    fn parses_line_breaks_in_quoted_fields() {
+        assert_eq!(parse_records("\"one\ntwo\",a\nb,c\n").unwrap(), vec![
+            (1, fields(&["one\ntwo", "a"])),
+            (3, fields(&["b", "c"])),
+        ]);
+    }
+
+    #[test]
+    fn ignores_bom() {
+        assert_eq!(parse_records("\u{feff}a,b\n").unwrap(), vec![(1, fields(&["a", "b"]))]);
+    }
+
+    #[test]
+    fn parses_crlf_line_breaks() {
+        assert_eq!(parse_records("a,b\r\n1,2\r\n").unwrap(), vec![
+            (1, fields(&["a", "b"])),
+            (2, fields(&["1", "2"])),
+        ]);
+    }
+
+    #[test]
+    fn keeps_crlf_in_quoted_fields() {
+        assert_eq!(parse_records("\"a\r\nb\",c\r\n").unwrap(), vec![(1, fields(&["a\r\nb", "c"]))]);
+    }
+
+    #[test]
+    fn fails_on_unterminated_quote() {
+        assert_eq!(parse_records("a,b\n\"c,d\n").unwrap_err(), (2, "Unterminated quoted field".to_string()));
+    }
+
+    #[test]
+    fn quotes_only_fields_that_need_it() {
+        assert_eq!(write_record(&fields(&["a", "b,c", "say \"hi\"", "x\ny"])), "a,\"b,c\",\"say \"\"hi\"\"\",\"x\ny\"");
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let records = vec![
+            fields(&["plain", "", "with,comma"]),
+            fields(&["\"quoted\"", "line\nbreak", "crlf\r\nbreak"]),
+            fields(&["trailing", "empty", ""]),
+        ];
+
+        let text: String = records.iter().map(|r| write_record(r) + "\n").collect();
+        let parsed: Vec<Vec<String>> = parse_records(&text).unwrap().into_iter().map(|(_, r)| r).collect();
+
+        assert_eq!(parsed, records);
+        assert_eq!(parsed.iter().map(|r| write_record(r) + "\n").collect::<String>(), text);
+    }
+}
@@ -1,11 +1,27 @@
 use std::{
-    fs::File,
+    fs,
     io,
-    io::{BufReader, BufRead}
+    path::Path,
 };
 
-pub fn read_lines(file_name: String) -> io::Lines<BufReader<File>> {
-    BufReader::new(
-        File::open(&*file_name).unwrap()
-    ).lines()
+/// Lists the sub directories (ending in '/') followed by the files with one of the given extensions, both sorted by name.
+pub fn read_directory(path: &Path, extensions: &[&str]) -> io::Result<Vec<String>> {
+    let mut directories: Vec<String> = vec![];
+    let mut files: Vec<String> = vec![];
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if entry.file_type()?.is_dir() {
+            directories.push(format!("{}/", name));
+        } else if extensions.iter().any(|e| name.ends_with(e)) {
+            files.push(name);
+        }
+    }
+
+    directories.sort();
+    files.sort();
+
+    Ok(directories.into_iter().chain(files).collect())
 }
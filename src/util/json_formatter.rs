@@ -0,0 +1,112 @@
+use std::io;
+
+use serde_json::ser::Formatter;
+
+use crate::util::number_format::format_number;
+
+/// Pretty printer producing the same layout as the catalogue files in `files/json`:
+/// two space indented objects, `"key" : value` pairs and arrays kept on the line of their elements (`[ {`, `}, {`, `} ]`).
+#[derive(Default)]
+pub struct CatalogueFormatter {
+    indent: usize,
+    has_value: bool,
+}
+
+impl CatalogueFormatter {
+    fn write_indent<W>(&self, writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        writer.write_all(b"\n")?;
+        writer.write_all("  ".repeat(self.indent).as_bytes())
+    }
+}
+
+impl Formatter for CatalogueFormatter {
+    fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        writer.write_all(format_number(value).as_bytes())
+    }
+
+    fn begin_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        writer.write_all(b"[")
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        writer.write_all(b" ]")
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        writer.write_all(if first { b" " } else { b", " })
+    }
+
+    fn end_array_value<W>(&mut self, _writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        Ok(())
+    }
+
+    fn begin_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        self.indent += 1;
+        self.has_value = false;
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        self.indent -= 1;
+
+        if self.has_value {
+            self.write_indent(writer)?;
+        } else {
+            writer.write_all(b" ")?;
+        }
+
+        // The enclosing object, if any, is always non-empty at this point
+        self.has_value = true;
+        writer.write_all(b"}")
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        if !first {
+            writer.write_all(b",")?;
+        }
+
+        self.write_indent(writer)
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        writer.write_all(b" : ")
+    }
+
+    fn end_object_value<W>(&mut self, _writer: &mut W) -> io::Result<()>
+        where
+            W: ?Sized + io::Write,
+    {
+        self.has_value = true;
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+pub mod csv;
 pub mod file_reader;
+pub mod json_formatter;
+pub mod number_format;
 pub mod ui;
 pub mod popup;
 pub mod state;
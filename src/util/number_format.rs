@@ -0,0 +1,35 @@
+/// Formats a number the way the catalogue files write them (Java's `Double.toString`),
+/// `1391400.0` and `0.387` in plain notation and `1.9885E30` outside of [10^-3, 10^7).
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0.0".to_string();
+    }
+
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+
+    if (-3..7).contains(&exponent) {
+        with_fraction(value.to_string())
+    } else {
+        format!("{}E{}", with_fraction(mantissa.to_string()), exponent)
+    }
+}
+
+/// Formats `value` like `format_number`, or as an empty string if it is not known.
+pub fn format_optional(value: Option<f64>) -> String {
+    value.map(format_number).unwrap_or_default()
+}
+
+/// Formats `value` like `format_number`, rounded to `digits` significant digits.
+pub fn format_rounded(value: f64, digits: usize) -> String {
+    format_number(format!("{:.*e}", digits.max(1) - 1, value).parse().unwrap_or_default())
+}
+
+fn with_fraction(number: String) -> String {
+    if number.contains('.') {
+        number
+    } else {
+        format!("{}.0", number)
+    }
+}
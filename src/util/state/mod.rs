@@ -1,3 +1,4 @@
 pub mod list;
+pub mod orbit_view;
 pub mod tabs;
 pub mod states;
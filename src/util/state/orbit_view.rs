@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use crate::planet_system::{
+    epoch::Epoch,
+    units::Time,
+};
+
+/// State of the animated orbit view, advanced by the ticks of the user interface.
+pub struct OrbitViewState {
+    pub paused: bool,
+    /// Days that pass in the view for every second
+    pub speed: f64,
+    /// Time the planets are drawn at, starting at the time the view was opened
+    pub epoch: Epoch,
+    /// How many times the view is enlarged, around the star
+    pub zoom: f64,
+    /// Distances from the star are drawn on a log scale, so the inner planets of compact systems can be told apart
+    pub log_scale: bool,
+    /// Why the last date that was typed in could not be used
+    pub error: Option<String>,
+    tick_rate: Duration,
+}
+
+impl OrbitViewState {
+    pub fn new(tick_rate: Duration) -> OrbitViewState {
+        OrbitViewState {
+            paused: false,
+            speed: 10.0,
+            epoch: Epoch::now(),
+            zoom: 1.0,
+            log_scale: false,
+            error: None,
+            tick_rate,
+        }
+    }
+
+    pub fn on_tick(&mut self) {
+        if !self.paused {
+            self.epoch = self.epoch.after(Time::from_days(self.speed * self.tick_rate.as_secs_f64()));
+        }
+    }
+
+    /// Moves the view to the date in `input`, and pauses it so the positions at that date can be read.
+    pub fn go_to(&mut self, input: &str) {
+        match input.parse::<Epoch>() {
+            Ok(epoch) => {
+                self.epoch = epoch;
+                self.paused = true;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub fn on_key(&mut self, c: char) {
+        match c {
+            'p' => self.paused = !self.paused,
+            '+' => self.speed *= 2.0,
+            '-' => self.speed /= 2.0,
+            'z' => self.zoom *= 1.5,
+            'Z' => self.zoom = (self.zoom / 1.5).max(1.0),
+            'l' => self.log_scale = !self.log_scale,
+            'r' => self.epoch = Epoch::now(),
+            _ => {}
+        }
+    }
+}
@@ -4,6 +4,7 @@ pub struct TabsState<'a> {
 }
 
 impl<'a> TabsState<'a> {
+    #[allow(mismatched_lifetime_syntaxes)]
     pub fn new(titles: Vec<&'a str>) -> TabsState {
         TabsState { titles, index: 0 }
     }
@@ -21,6 +22,7 @@ impl<'a> TabsState<'a> {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl<'a> Default for TabsState<'a> {
     fn default() -> Self {
         TabsState {
@@ -14,6 +14,7 @@ use ratatui::{
 
 use crate::{
     app::app::App,
+    planet_system::celestial_body::CelestialBody,
     util::state::states::{PopupMode, InputMode}
 };
 
@@ -59,16 +60,44 @@ pub fn draw_input<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
     )
 }
 
+pub fn draw_messages<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+    where
+        B: Backend,
+{
+    let messages: Vec<ListItem> = app.messages
+        .iter()
+        .rev()
+        .map(|m| ListItem::new(Line::from(m.clone())))
+        .collect();
+
+    f.render_widget(
+        List::new(messages)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .title("Messages")
+            ),
+        area
+    );
+}
+
+/// Derived properties of `body` as "name: value", followed by `extra` properties that depend on more than the body.
+pub fn derived_rows(body: &dyn CelestialBody, extra: Vec<(&'static str, String)>) -> Vec<String> {
+    body.derived_fields().into_iter()
+        .chain(extra)
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect()
+}
+
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
-            rect_constraints(percent_x).as_ref(),
+            rect_constraints(percent_x),
         )
         .split(
             Layout::default()
                 .constraints(
-                    rect_constraints(percent_y).as_ref(),
+                    rect_constraints(percent_y),
                 )
                 .split(r)[1]
         )[1]